@@ -10,7 +10,7 @@ use tiny_varint::{
     encode, decode,
     encode_zigzag, decode_zigzag,
     VarIntEncoder, VarIntDecoder,
-    Error
+    Error, MAX_VARINT_LEN
 };
 
 fn main() {
@@ -21,12 +21,12 @@ fn main() {
     let values = [0u64, 127, 128, 16383, 16384, 2097151];
     
     for &value in &values {
-        let mut buf = [0u8; 10];
+        let mut buf = [0u8; MAX_VARINT_LEN];
         let bytes_written = encode(value, &mut buf).unwrap();
         
         print!("  Value {} encoded as {} bytes: [ ", value, bytes_written);
-        for i in 0..bytes_written {
-            print!("{:#04x} ", buf[i]);
+        for byte in &buf[..bytes_written] {
+            print!("{:#04x} ", byte);
         }
         println!("]");
         
@@ -40,12 +40,12 @@ fn main() {
     let signed_values = [0i32, 1, -1, 2, -2, 127, -127, 128, -128];
     
     for &value in &signed_values {
-        let mut buf = [0u8; 10];
+        let mut buf = [0u8; MAX_VARINT_LEN];
         let bytes_written = encode_zigzag(value, &mut buf).unwrap();
         
         print!("  Value {} encoded as {} bytes: [ ", value, bytes_written);
-        for i in 0..bytes_written {
-            print!("{:#04x} ", buf[i]);
+        for byte in &buf[..bytes_written] {
+            print!("{:#04x} ", byte);
         }
         println!("]");
         
@@ -66,8 +66,8 @@ fn main() {
     println!("  Encoded {} values using {} bytes", batch_values.len(), bytes_written);
     
     print!("  Encoded bytes: [ ");
-    for i in 0..bytes_written {
-        print!("{:#04x} ", buffer[i]);
+    for byte in &buffer[..bytes_written] {
+        print!("{:#04x} ", byte);
     }
     println!("]");
     
@@ -77,8 +77,8 @@ fn main() {
     let count = decoder.read_batch(&mut decoded).unwrap();
     
     print!("  Decoded values: [ ");
-    for i in 0..count {
-        print!("{} ", decoded[i]);
+    for value in &decoded[..count] {
+        print!("{} ", value);
     }
     println!("]");
     println!("  Read {} bytes in total", decoder.position());
@@ -88,12 +88,12 @@ fn main() {
     
     // Using u64 type
     let value = 16384u64;
-    let mut buf = [0u8; 10];
+    let mut buf = [0u8; MAX_VARINT_LEN];
     let size = encode(value, &mut buf).unwrap();
     
     print!("  u64 value {} encoded as {} bytes: [ ", value, size);
-    for i in 0..size {
-        print!("{:#04x} ", buf[i]);
+    for byte in &buf[..size] {
+        print!("{:#04x} ", byte);
     }
     println!("]");
     
@@ -102,12 +102,12 @@ fn main() {
     
     // Using u32 type
     let u32_value = 42u32;
-    let mut buf = [0u8; 10];
+    let mut buf = [0u8; MAX_VARINT_LEN];
     let size = encode(u32_value, &mut buf).unwrap();
     
     print!("  u32 value {} encoded as {} bytes: [ ", u32_value, size);
-    for i in 0..size {
-        print!("{:#04x} ", buf[i]);
+    for byte in &buf[..size] {
+        print!("{:#04x} ", byte);
     }
     println!("]");
     
@@ -119,8 +119,8 @@ fn main() {
     let size = encode(i16_value, &mut buf).unwrap();
     
     print!("  i16 value {} encoded as {} bytes: [ ", i16_value, size);
-    for i in 0..size {
-        print!("{:#04x} ", buf[i]);
+    for byte in &buf[..size] {
+        print!("{:#04x} ", byte);
     }
     println!("]");
     
@@ -145,7 +145,7 @@ fn main() {
     
     match decode::<u64>(&incomplete_buf) {
         Ok(_) => println!("  Decoding successful (should not reach here)"),
-        Err(Error::InputTooShort) => {
+        Err(Error::UnexpectedEof) => {
             println!("  Input too short: high bit is 1 indicating more bytes follow, but data ended");
         }
         Err(e) => println!("  Error occurred: {:?}", e),
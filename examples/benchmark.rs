@@ -1,4 +1,5 @@
-use tiny_varint::{VarintValue, varint, encode, decode};
+use tiny_varint::{VarintValue, varint, encode, decode, decode_batch_trusted, VarIntEncoder, VarIntDecoder};
+use std::hint::black_box;
 use std::time::{Instant, Duration};
 
 // Simple benchmark helper structure
@@ -136,6 +137,62 @@ fn main() {
     });
     benchmark.report();
     
+    // 7. Valid-input-heavy workload: decode a long stream of u64 values
+    let stream_values: Vec<u64> = (0..1024u64).map(|i| i * i * 97).collect();
+    let mut stream_buffer = vec![0u8; stream_values.len() * 10];
+    let stream_len = VarIntEncoder::new(&mut stream_buffer)
+        .write_batch(&stream_values)
+        .unwrap();
+    let mut stream_decoded = vec![0u64; stream_values.len()];
+    
+    let mut benchmark = Benchmark::new("u64 stream decoding (1024 values)", ITERATIONS / 1000);
+    benchmark.run(|| {
+        let mut decoder = VarIntDecoder::<u64>::new(black_box(&stream_buffer[..stream_len]));
+        black_box(decoder.read_batch(&mut stream_decoded)).unwrap();
+    });
+    benchmark.report();
+    
+    // 8. Common case: values that fit in one or two bytes, against wider ones
+    let small_values: Vec<u64> = (0..1024u64).map(|i| (i * 37) % 16384).collect();
+    let wide_values: Vec<u64> = (0..1024u64).map(|i| (i + 1) << 30).collect();
+    
+    let mut benchmark = Benchmark::new("u64 small value encoding (1024 values, 1-2 bytes)", ITERATIONS / 1000);
+    benchmark.run(|| {
+        for value in black_box(&small_values) {
+            black_box(encode(*value, &mut buffer)).unwrap();
+        }
+    });
+    benchmark.report();
+    
+    let mut benchmark = Benchmark::new("u64 wide value encoding (1024 values, 5-7 bytes)", ITERATIONS / 1000);
+    benchmark.run(|| {
+        for value in black_box(&wide_values) {
+            black_box(encode(*value, &mut buffer)).unwrap();
+        }
+    });
+    benchmark.report();
+    
+    // 9. Trusted bulk decoding against the fully checked read_batch
+    let u32_stream: Vec<u32> = (0..4096u32).map(|i| i.wrapping_mul(2_654_435_761) >> (i % 32)).collect();
+    let mut u32_stream_buffer = vec![0u8; u32_stream.len() * 5];
+    let u32_stream_len = VarIntEncoder::new(&mut u32_stream_buffer)
+        .write_batch(&u32_stream)
+        .unwrap();
+    let mut u32_stream_decoded = vec![0u32; u32_stream.len()];
+    
+    let mut benchmark = Benchmark::new("u32 read_batch (4096 values)", ITERATIONS / 1000);
+    benchmark.run(|| {
+        let mut decoder = VarIntDecoder::<u32>::new(black_box(&u32_stream_buffer[..u32_stream_len]));
+        black_box(decoder.read_batch(&mut u32_stream_decoded)).unwrap();
+    });
+    benchmark.report();
+    
+    let mut benchmark = Benchmark::new("u32 decode_batch_trusted (4096 values)", ITERATIONS / 1000);
+    benchmark.run(|| {
+        black_box(decode_batch_trusted(black_box(&u32_stream_buffer[..u32_stream_len]), &mut u32_stream_decoded)).unwrap();
+    });
+    benchmark.report();
+    
     println!("\nPerformance Summary:");
     println!("1. VarintValue type information introduces some performance overhead");
     println!("2. Optimizations (special zero handling, avoiding temporary buffers, etc.) effectively improve performance");
@@ -9,7 +9,7 @@ extern crate tiny_varint;
 use tiny_varint::{
     bytes_of, values_from,
     VarIntEncoder,
-    encode_zigzag
+    encode_zigzag, MAX_VARINT_LEN
 };
 
 fn main() {
@@ -29,12 +29,12 @@ fn main() {
     println!("]");
     
     // Manually verify the result
-    let mut manual_buf = [0u8; 10];
+    let mut manual_buf = [0u8; MAX_VARINT_LEN];
     let manual_size = tiny_varint::encode(value, &mut manual_buf).unwrap();
     
     print!("  Verification bytes: [ ");
-    for i in 0..manual_size {
-        print!("{:#04x} ", manual_buf[i]);
+    for byte in &manual_buf[..manual_size] {
+        print!("{:#04x} ", byte);
     }
     println!("]\n");
     
@@ -49,8 +49,8 @@ fn main() {
     let bytes_written = encoder.write_batch(&values).unwrap();
     
     print!("  Encoded bytes: [ ");
-    for i in 0..bytes_written {
-        print!("{:#04x} ", buffer[i]);
+    for byte in &buffer[..bytes_written] {
+        print!("{:#04x} ", byte);
     }
     println!("]");
     
@@ -71,12 +71,12 @@ fn main() {
     // Create a zigzag-encoded byte iterator for each value
     for &value in &signed_values {
         // First apply zigzag encoding
-        let mut buf = [0u8; 10];
+        let mut buf = [0u8; MAX_VARINT_LEN];
         let size = encode_zigzag(value, &mut buf).unwrap();
         
         print!("  ZigZag encoding for {}: [ ", value);
-        for i in 0..size {
-            print!("{:#04x} ", buf[i]);
+        for byte in &buf[..size] {
+            print!("{:#04x} ", byte);
         }
         println!("]");
     }
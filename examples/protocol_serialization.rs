@@ -6,7 +6,7 @@
 
 use tiny_varint::{
     encode_zigzag,
-    VarIntEncoder, VarIntDecoder, Error, varint_size
+    VarIntEncoder, VarIntDecoder, Error, varint_size, MAX_VARINT_LEN
 };
 
 /// A simple message structure that can be serialized and deserialized
@@ -32,10 +32,10 @@ impl SimpleMessage {
         let id_size = varint_size(self.message_id);
         
         // Create temporary buffers for temperature and humidity to calculate size
-        let mut temp_buf = [0u8; 10];
+        let mut temp_buf = [0u8; MAX_VARINT_LEN];
         let temp_size = encode_zigzag(self.temperature, &mut temp_buf).unwrap();
         
-        let mut hum_buf = [0u8; 10];
+        let mut hum_buf = [0u8; MAX_VARINT_LEN];
         let humidity_size = encode_zigzag(self.humidity, &mut hum_buf).unwrap();
         
         // Payload size needs a length field + the payload itself
@@ -62,18 +62,9 @@ impl SimpleMessage {
         encoder.write(self.payload.len() as u32)?;
         
         // Write payload bytes
-        let current_offset = encoder.position();
-        if current_offset + self.payload.len() > buffer.len() {
-            return Err(Error::BufferTooSmall {
-                needed: current_offset + self.payload.len(),
-                actual: buffer.len(),
-            });
-        }
+        encoder.write_raw(&self.payload)?;
         
-        buffer[current_offset..current_offset + self.payload.len()]
-            .copy_from_slice(&self.payload);
-            
-        Ok(current_offset + self.payload.len())
+        Ok(encoder.position())
     }
     
     /// Deserialize message from byte array
@@ -90,21 +81,17 @@ impl SimpleMessage {
         let humidity = decoder.read_zigzag::<i8>()?;
         
         // Decode payload length
+        let len_offset = decoder.position();
         let payload_len = decoder.read()? as usize;
         if payload_len != 16 {
-            return Err(Error::InvalidEncoding);
+            return Err(Error::InvalidEncoding { offset: len_offset });
         }
         
         // Decode payload
-        let current_offset = decoder.position();
-        if current_offset + payload_len > bytes.len() {
-            return Err(Error::InputTooShort);
-        }
-        
         let mut payload = [0u8; 16];
-        payload.copy_from_slice(&bytes[current_offset..current_offset + payload_len]);
+        payload.copy_from_slice(decoder.read_raw(payload_len)?);
         
-        let final_offset = current_offset + payload_len;
+        let final_offset = decoder.position();
         
         let msg = SimpleMessage {
             message_id,
@@ -122,8 +109,8 @@ fn main() {
     
     // Create an example message
     let mut payload = [0u8; 16];
-    for i in 0..payload.len() {
-        payload[i] = (i * 10) as u8;
+    for (i, byte) in payload.iter_mut().enumerate() {
+        *byte = (i * 10) as u8;
     }
     
     let original_msg = SimpleMessage {
@@ -148,8 +135,8 @@ fn main() {
             println!("Serialization successful, used {} bytes", size);
             
             print!("Serialized bytes: [ ");
-            for i in 0..size {
-                print!("{:#04x} ", buffer[i]);
+            for byte in &buffer[..size] {
+                print!("{:#04x} ", byte);
             }
             println!("]");
             
@@ -1,4 +1,4 @@
-use tiny_varint::{VarintValue, varint, encode};
+use tiny_varint::{VarintValue, varint, varint_values_from, to_bytes_batch, encode};
 
 fn main() {
     println!("VarintValue Mixed Type Example");
@@ -24,33 +24,23 @@ fn main() {
         println!("  [{}]: {:?}", i, value);
     }
     
-    // Serialize each value
+    // Serialize the values back to back
     let mut buffer = [0u8; 100];
-    let mut pos = 0;
-    
-    for value in &values {
-        let bytes_written = value.to_bytes(&mut buffer[pos..]).unwrap();
-        pos += bytes_written;
-    }
+    let pos = to_bytes_batch(&values, &mut buffer).unwrap();
     
     println!("\nSerialized {} bytes total", pos);
     println!("Encoded bytes: ");
     print!("  [ ");
-    for i in 0..pos {
-        print!("{:#04x} ", buffer[i]);
+    for byte in &buffer[..pos] {
+        print!("{:#04x} ", byte);
     }
     println!("]");
     
     // Deserialize values
     println!("\nDecoded values:");
-    let mut read_pos = 0;
-    let mut index = 0;
-    
-    while read_pos < pos {
-        let (value, bytes_read) = VarintValue::from_bytes(&buffer[read_pos..pos]).unwrap();
-        println!("  [{}]: {:?} (read {} bytes)", index, value, bytes_read);
-        read_pos += bytes_read;
-        index += 1;
+    for (index, value) in varint_values_from(&buffer[..pos]).enumerate() {
+        let value = value.unwrap();
+        println!("  [{}]: {:?} ({} bytes)", index, value, value.serialized_size());
     }
     
     // 2. Size Comparison
@@ -108,7 +98,7 @@ fn main() {
         .sum();
     
     println!("Mixed value sizes:");
-    for (_i, value) in mixed_values.iter().enumerate() {
+    for value in mixed_values.iter() {
         println!("  {:?}: {} bytes", value, value.serialized_size());
     }
     println!("Total serialized size: {} bytes", total_size);
@@ -166,8 +156,8 @@ fn main() {
     println!("Message serialized to {} bytes", message_pos);
     println!("Message bytes: ");
     print!("  [ ");
-    for i in 0..message_pos {
-        print!("{:#04x} ", message_buffer[i]);
+    for byte in &message_buffer[..message_pos] {
+        print!("{:#04x} ", byte);
     }
     println!("]");
     
@@ -218,8 +208,8 @@ fn main() {
                     message_pos - read_pos, read_pos, message_pos);
                 // Display remaining bytes in hex to aid debugging
                 print!("    Remaining bytes hex: [");
-                for j in read_pos..message_pos {
-                    print!("{:#04x} ", message_buffer[j]);
+                for byte in &message_buffer[read_pos..message_pos] {
+                    print!("{:#04x} ", byte);
                 }
                 println!("]");
                 break;
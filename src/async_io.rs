@@ -0,0 +1,72 @@
+//! Async varint reading and writing over tokio's `AsyncRead`/`AsyncWrite`
+
+use std::io;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use crate::traits::{VarInt, VarIntOps};
+use crate::encoding::{encode_array, push_byte};
+use crate::io::to_io_error;
+
+/// Reads a VarInt value from an async source, one byte at a time
+///
+/// # Errors
+/// * Returns `io::ErrorKind::UnexpectedEof` if the source ends mid-value
+/// * Returns `io::ErrorKind::InvalidData` if the value overflows or cannot be represented in `T`
+pub async fn read_varint<R: AsyncRead + Unpin, T: VarInt>(r: &mut R) -> io::Result<T> {
+    let mut result = T::Unsigned::from_byte(0, 0);
+    let mut shift = 0;
+    
+    loop {
+        let byte = r.read_u8().await?;
+        
+        if let Some(value) = push_byte(&mut result, byte, &mut shift).map_err(to_io_error)? {
+            return Ok(value);
+        }
+    }
+}
+
+/// Writes a VarInt value to an async sink
+///
+/// Returns the number of bytes written
+pub async fn write_varint<W: AsyncWrite + Unpin, T: VarInt>(w: &mut W, value: T) -> io::Result<usize> {
+    let (buf, len) = encode_array(value);
+    w.write_all(&buf[..len]).await?;
+    Ok(len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio_test::block_on;
+    
+    #[test]
+    fn test_duplex_round_trip() {
+        block_on(async {
+            let (mut client, mut server) = tokio::io::duplex(64);
+            
+            assert_eq!(write_varint(&mut client, 300u32).await.unwrap(), 2);
+            assert_eq!(write_varint(&mut client, -2i64).await.unwrap(), 10);
+            assert_eq!(write_varint(&mut client, u128::MAX).await.unwrap(), 19);
+            
+            assert_eq!(read_varint::<_, u32>(&mut server).await.unwrap(), 300);
+            assert_eq!(read_varint::<_, i64>(&mut server).await.unwrap(), -2);
+            assert_eq!(read_varint::<_, u128>(&mut server).await.unwrap(), u128::MAX);
+        });
+    }
+    
+    #[test]
+    fn test_read_errors() {
+        block_on(async {
+            let (mut client, mut server) = tokio::io::duplex(64);
+            client.write_all(&[0x80, 0x80, 0x80, 0x01]).await.unwrap();
+            let err = read_varint::<_, u16>(&mut server).await.unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+            
+            // Continuation bit set, then the writer goes away
+            let (mut client, mut server) = tokio::io::duplex(64);
+            client.write_all(&[0x80]).await.unwrap();
+            drop(client);
+            let err = read_varint::<_, u64>(&mut server).await.unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+        });
+    }
+}
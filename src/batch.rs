@@ -1,8 +1,13 @@
 use crate::error::Error;
-use crate::traits::VarInt;
-use crate::encoding::{encode, decode};
-use crate::zigzag::{ZigZag, encode_zigzag, decode_zigzag};
+use crate::traits::{VarInt, VarIntOps};
+use crate::encoding::{MAX_VARINT_LEN, encode, decode};
+use crate::zigzag::{ZigZag, zigzag_size, encode_zigzag, decode_zigzag};
 use core::marker::PhantomData;
+use core::ops::Add;
+#[cfg(feature = "alloc")]
+use crate::iter::{bytes_of, values_from};
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
 
 /// Batch encoder for VarInt values with state management
 pub struct VarIntEncoder<'a, T: VarInt = u64> {
@@ -31,15 +36,51 @@ impl<'a, T: VarInt> VarIntEncoder<'a, T> {
         self.buf.len() - self.pos
     }
     
+    /// Gets the bytes written so far
+    pub fn written(&self) -> &[u8] {
+        &self.buf[..self.pos]
+    }
+    
+    /// Gets the unwritten tail of the buffer
+    ///
+    /// Bytes written here directly are not tracked by the encoder's position
+    pub fn remaining_mut(&mut self) -> &mut [u8] {
+        &mut self.buf[self.pos..]
+    }
+    
+    /// Consumes the encoder, returning the whole buffer and the number of bytes written
+    ///
+    /// The encoded data is `&buf[..len]`
+    pub fn finish(self) -> (&'a mut [u8], usize) {
+        (self.buf, self.pos)
+    }
+    
+    /// Moves the position back to the start so the buffer can be reused
+    ///
+    /// The buffer is not zeroed; previously written bytes stay until overwritten
+    pub fn reset(&mut self) {
+        self.pos = 0;
+    }
+    
+    /// Swaps in a new buffer and moves the position back to the start
+    pub fn reset_with(&mut self, buf: &'a mut [u8]) {
+        self.buf = buf;
+        self.pos = 0;
+    }
+    
     /// Writes a VarInt value to the buffer
     ///
     /// Returns the number of bytes written
+    ///
+    /// # Errors
+    /// Returns `Error::BufferTooSmall` with the value's size and the remaining space
+    /// if the value does not fit; nothing is written and the position is unchanged
     pub fn write(&mut self, value: T) -> Result<usize, Error> {
-        if self.pos >= self.buf.len() {
-            return Err(Error::BufferTooSmall {
-                needed: self.pos + 1,
-                actual: self.buf.len(),
-            });
+        let needed_size = value.varint_size();
+        let remaining = self.remaining();
+        
+        if needed_size > remaining {
+            return Err(Error::buffer_too_small(needed_size, remaining));
         }
         
         let bytes_written = encode(value, &mut self.buf[self.pos..])?;
@@ -47,6 +88,70 @@ impl<'a, T: VarInt> VarIntEncoder<'a, T> {
         Ok(bytes_written)
     }
     
+    /// Writes a VarInt value over `reserved` bytes set aside earlier, without moving the position
+    ///
+    /// Used to patch a header field once the body after it has been written.
+    /// The caller is responsible for reserving the space, for example with
+    /// `write_raw`, and since varint length depends on the value it must reserve
+    /// enough bytes for the largest value the field can take. A shorter value is
+    /// padded to exactly `reserved` bytes with zero continuation groups
+    /// (`0x80 ... 0x00`), which `decode` reads back as the same value but
+    /// `decode_canonical` rejects.
+    ///
+    /// Returns the number of bytes written, always `reserved`
+    ///
+    /// # Errors
+    /// * Returns `Error::BufferTooSmall` with the value's size and `reserved` if the value needs more bytes
+    /// * Returns `Error::ValueTooLarge` if `reserved` is longer than `T::MAX_ENCODED_LEN`, since
+    ///   the padding could not be decoded as `T`
+    /// * Returns `Error::BufferTooSmall` with the end of the field and the current
+    ///   position if the field runs past the bytes written so far
+    ///
+    /// Nothing is written on error.
+    pub fn write_at(&mut self, offset: usize, reserved: usize, value: T) -> Result<usize, Error> {
+        let needed_size = value.varint_size();
+        if needed_size > reserved {
+            return Err(Error::buffer_too_small(needed_size, reserved));
+        }
+        if reserved > T::MAX_ENCODED_LEN {
+            return Err(Error::value_too_large(reserved, T::MAX_ENCODED_LEN));
+        }
+        
+        let end = offset.saturating_add(reserved);
+        if end > self.pos {
+            return Err(Error::buffer_too_small(end, self.pos));
+        }
+        
+        let field = &mut self.buf[offset..end];
+        encode(value, field)?;
+        
+        // Pad with empty groups so no stale byte is left in the reservation
+        if needed_size < reserved {
+            field[needed_size - 1] |= 0x80;
+            field[needed_size..reserved - 1].fill(0x80);
+            field[reserved - 1] = 0x00;
+        }
+        
+        Ok(reserved)
+    }
+    
+    /// Copies raw bytes into the buffer, for fixed-size fields between varints
+    ///
+    /// Returns the number of bytes written
+    ///
+    /// # Errors
+    /// Returns `Error::BufferTooSmall` if the bytes do not fit; nothing is written
+    pub fn write_raw(&mut self, bytes: &[u8]) -> Result<usize, Error> {
+        let end = self.pos + bytes.len();
+        if end > self.buf.len() {
+            return Err(Error::buffer_too_small(end, self.buf.len()));
+        }
+        
+        self.buf[self.pos..end].copy_from_slice(bytes);
+        self.pos = end;
+        Ok(bytes.len())
+    }
+    
     /// Writes a batch of VarInt values
     ///
     /// Returns the total number of bytes written
@@ -58,6 +163,50 @@ impl<'a, T: VarInt> VarIntEncoder<'a, T> {
         Ok(self.pos - start_pos)
     }
     
+    /// Writes as many values from the front of a batch as fit, stopping before the first that does not
+    ///
+    /// Meant for filling fixed-size packets: the values from `values_written`
+    /// onwards go into the next buffer. Only complete values are written, so
+    /// the position always sits on a value boundary.
+    ///
+    /// Returns `(values_written, bytes_written)`
+    pub fn write_batch_partial(&mut self, values: &[T]) -> (usize, usize) {
+        let start_pos = self.pos;
+        let mut count = 0;
+        
+        for &value in values {
+            if self.write(value).is_err() {
+                break;
+            }
+            count += 1;
+        }
+        
+        (count, self.pos - start_pos)
+    }
+    
+    /// Writes a batch of VarInt values, recording where each one starts
+    ///
+    /// `offsets[i]` receives the buffer position of `values[i]`, so single
+    /// values can later be decoded with `read_at` without scanning from the start.
+    ///
+    /// Returns the total number of bytes written
+    ///
+    /// # Errors
+    /// * Returns `Error::BufferTooSmall` if `offsets` is shorter than `values`, with the counts in values; nothing is written
+    /// * Returns `Error::BufferTooSmall` if the buffer fills up
+    pub fn write_batch_offsets(&mut self, values: &[T], offsets: &mut [usize]) -> Result<usize, Error> {
+        if offsets.len() < values.len() {
+            return Err(Error::buffer_too_small(values.len(), offsets.len()));
+        }
+        
+        let start_pos = self.pos;
+        for (&value, offset) in values.iter().zip(offsets.iter_mut()) {
+            *offset = self.pos;
+            self.write(value)?;
+        }
+        Ok(self.pos - start_pos)
+    }
+    
     /// Writes a u64 value to the buffer (convenience method)
     ///
     /// Returns the number of bytes written
@@ -83,11 +232,11 @@ impl<'a, T: VarInt> VarIntEncoder<'a, T> {
     where 
         S: ZigZag,
         S::Unsigned: VarInt {
-        if self.pos >= self.buf.len() {
-            return Err(Error::BufferTooSmall {
-                needed: self.pos + 1,
-                actual: self.buf.len(),
-            });
+        let needed_size = zigzag_size(value);
+        let remaining = self.remaining();
+        
+        if needed_size > remaining {
+            return Err(Error::buffer_too_small(needed_size, remaining));
         }
         
         let bytes_written = encode_zigzag(value, &mut self.buf[self.pos..])?;
@@ -110,6 +259,71 @@ impl<'a, T: VarInt> VarIntEncoder<'a, T> {
     }
 }
 
+/// Creates an encoder over an empty buffer, as a placeholder until `reset_with`
+/// supplies real storage
+impl<T: VarInt> Default for VarIntEncoder<'_, T> {
+    fn default() -> Self {
+        VarIntEncoder::new(&mut [])
+    }
+}
+
+/// `Extend` adaptor that writes values through a `VarIntEncoder`
+///
+/// `Extend` cannot return errors, so the first error is stored and all
+/// further values are ignored; check `result()` after extending.
+pub struct VarIntSink<'a, T: VarInt = u64> {
+    encoder: VarIntEncoder<'a, T>,
+    bytes_written: usize,
+    error: Option<Error>,
+}
+
+impl<'a, T: VarInt> VarIntSink<'a, T> {
+    /// Creates a new sink writing through the encoder
+    pub fn new(encoder: VarIntEncoder<'a, T>) -> Self {
+        VarIntSink {
+            encoder,
+            bytes_written: 0,
+            error: None,
+        }
+    }
+    
+    /// Gets the number of bytes written through the sink
+    pub fn bytes_written(&self) -> usize {
+        self.bytes_written
+    }
+    
+    /// Returns the number of bytes written, or the first error encountered
+    pub fn result(&self) -> Result<usize, Error> {
+        match self.error {
+            Some(e) => Err(e),
+            None => Ok(self.bytes_written),
+        }
+    }
+    
+    /// Gets back the underlying encoder
+    pub fn into_encoder(self) -> VarIntEncoder<'a, T> {
+        self.encoder
+    }
+}
+
+impl<T: VarInt> Extend<T> for VarIntSink<'_, T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, values: I) {
+        if self.error.is_some() {
+            return;
+        }
+        
+        for value in values {
+            match self.encoder.write(value) {
+                Ok(bytes_written) => self.bytes_written += bytes_written,
+                Err(e) => {
+                    self.error = Some(e);
+                    return;
+                }
+            }
+        }
+    }
+}
+
 /// Batch decoder for VarInt values with state management
 pub struct VarIntDecoder<'a, T: VarInt = u64> {
     buf: &'a [u8],
@@ -137,12 +351,25 @@ impl<'a, T: VarInt> VarIntDecoder<'a, T> {
         &self.buf[self.pos..]
     }
     
+    /// Counts the values left in the buffer without decoding them or moving the position
+    ///
+    /// # Errors
+    /// Returns `Error::UnexpectedEof` if the buffer ends with a dangling continuation byte
+    pub fn remaining_count(&self) -> Result<usize, Error> {
+        count_varints(self.remaining())
+    }
+    
+    /// Moves the position back to the start so the buffer can be read again
+    pub fn reset(&mut self) {
+        self.pos = 0;
+    }
+    
     /// Reads a VarInt value from the buffer
     ///
     /// Returns the decoded value
     pub fn read(&mut self) -> Result<T, Error> {
         if self.pos >= self.buf.len() {
-            return Err(Error::InputTooShort);
+            return Err(Error::input_too_short());
         }
         
         let (value, bytes_read) = decode(&self.buf[self.pos..])?;
@@ -150,8 +377,73 @@ impl<'a, T: VarInt> VarIntDecoder<'a, T> {
         Ok(value)
     }
     
+    /// Returns an iterator that reads values until the buffer is used up
+    ///
+    /// The iterator shares this decoder's position, so it can be combined with
+    /// adaptors like `take` and followed by direct `read` calls. It stops after
+    /// yielding the first error, leaving the position at the failing value.
+    pub fn iter(&mut self) -> VarIntDecoderIter<'_, 'a, T> {
+        VarIntDecoderIter {
+            decoder: self,
+            failed: false,
+        }
+    }
+    
+    /// Reads `len` raw bytes, for fixed-size fields between varints
+    ///
+    /// Returns the bytes, borrowed from the input
+    ///
+    /// # Errors
+    /// Returns `Error::InputTooShort` if fewer than `len` bytes remain; the position is unchanged
+    pub fn read_raw(&mut self, len: usize) -> Result<&'a [u8], Error> {
+        if len > self.buf.len() - self.pos {
+            return Err(Error::input_too_short());
+        }
+        
+        let bytes = &self.buf[self.pos..self.pos + len];
+        self.pos += len;
+        Ok(bytes)
+    }
+    
+    /// Decodes the next VarInt value without advancing the position
+    ///
+    /// Returns the decoded value
+    #[inline]
+    pub fn peek(&self) -> Result<T, Error> {
+        self.peek_with_size().map(|(value, _)| value)
+    }
+    
+    /// Decodes the next VarInt value without advancing the position
+    ///
+    /// Returns the decoded value and the number of bytes it occupies
+    pub fn peek_with_size(&self) -> Result<(T, usize), Error> {
+        if self.pos >= self.buf.len() {
+            return Err(Error::input_too_short());
+        }
+        
+        decode(&self.buf[self.pos..])
+    }
+    
+    /// Decodes the next zigzag-encoded signed value without advancing the position
+    ///
+    /// Returns the decoded value
+    pub fn peek_zigzag<S>(&self) -> Result<S, Error>
+    where 
+        S: ZigZag,
+        S::Unsigned: VarInt {
+        if self.pos >= self.buf.len() {
+            return Err(Error::input_too_short());
+        }
+        
+        decode_zigzag(&self.buf[self.pos..]).map(|(value, _)| value)
+    }
+    
     /// Reads a batch of VarInt values into the provided buffer
     ///
+    /// This is best-effort: reading stops once `values` is full, leaving any
+    /// further input for a later call. Use `read_batch_checked` to treat
+    /// leftover input as an error.
+    ///
     /// Returns the number of values read
     pub fn read_batch(&mut self, values: &mut [T]) -> Result<usize, Error> {
         let mut count = 0;
@@ -162,7 +454,8 @@ impl<'a, T: VarInt> VarIntDecoder<'a, T> {
                     values[count] = value;
                     count += 1;
                 }
-                Err(Error::InputTooShort) => break,
+                // Trailing partial value, left in place for a later read
+                Err(Error::UnexpectedEof) => break,
                 Err(e) => return Err(e),
             }
         }
@@ -170,6 +463,66 @@ impl<'a, T: VarInt> VarIntDecoder<'a, T> {
         Ok(count)
     }
     
+    /// Reads a batch of VarInt values, requiring `values` to hold everything left in the buffer
+    ///
+    /// Unlike `read_batch`, a full `values` with input still undecoded is an error
+    /// rather than a short read. On error the position is left where it was before the call.
+    ///
+    /// Returns the number of values read
+    ///
+    /// # Errors
+    /// * Returns `Error::BufferTooSmall` if `values` fills up before the input is used up, with the counts in values
+    /// * Returns `Error::UnexpectedEof` if the buffer ends with a partial value
+    /// * Returns any error from decoding the values themselves
+    pub fn read_batch_checked(&mut self, values: &mut [T]) -> Result<usize, Error> {
+        let start_pos = self.pos;
+        
+        let result = self.read_batch(values).and_then(|count| {
+            if self.pos == self.buf.len() {
+                return Ok(count);
+            }
+            
+            // Either values is full or only a partial value is left
+            let undecoded = self.remaining_count()?;
+            Err(Error::buffer_too_small(count + undecoded, values.len()))
+        });
+        
+        if result.is_err() {
+            self.pos = start_pos;
+        }
+        
+        result
+    }
+    
+    /// Reads exactly `values.len()` values, requiring them to use up the rest of the buffer
+    ///
+    /// On error the position is left where it was before the call.
+    ///
+    /// # Errors
+    /// * Returns `Error::InputTooShort` if fewer values are present
+    /// * Returns `Error::TrailingData` if bytes remain after the last value
+    /// * Returns any error from decoding the values themselves
+    pub fn read_batch_exact(&mut self, values: &mut [T]) -> Result<(), Error> {
+        let start_pos = self.pos;
+        
+        for slot in values.iter_mut() {
+            match self.read() {
+                Ok(value) => *slot = value,
+                Err(e) => {
+                    self.pos = start_pos;
+                    return Err(e);
+                }
+            }
+        }
+        
+        if self.pos != self.buf.len() {
+            self.pos = start_pos;
+            return Err(Error::trailing_data());
+        }
+        
+        Ok(())
+    }
+    
     /// Reads a u64 value from the buffer (convenience method)
     ///
     /// Returns the decoded value
@@ -196,7 +549,7 @@ impl<'a, T: VarInt> VarIntDecoder<'a, T> {
         S: ZigZag,
         S::Unsigned: VarInt {
         if self.pos >= self.buf.len() {
-            return Err(Error::InputTooShort);
+            return Err(Error::input_too_short());
         }
         
         let (value, bytes_read) = decode_zigzag(&self.buf[self.pos..])?;
@@ -204,6 +557,14 @@ impl<'a, T: VarInt> VarIntDecoder<'a, T> {
         Ok(value)
     }
     
+    /// Reads a zigzag-encoded value as the signed counterpart of the decoder's width
+    ///
+    /// A `VarIntDecoder<u32>` reads an `i32`, without naming the type as `read_zigzag` needs.
+    pub fn read_zigzag_signed(&mut self) -> Result<T::Signed, Error>
+    where T::Signed: ZigZag<Unsigned = T> {
+        self.read_zigzag()
+    }
+    
     /// Reads a batch of signed values that were encoded using zigzag encoding
     ///
     /// Returns the number of values read
@@ -219,7 +580,8 @@ impl<'a, T: VarInt> VarIntDecoder<'a, T> {
                     values[count] = value;
                     count += 1;
                 }
-                Err(Error::InputTooShort) => break,
+                // Trailing partial value, left in place for a later read
+                Err(Error::UnexpectedEof) => break,
                 Err(e) => return Err(e),
             }
         }
@@ -228,6 +590,33 @@ impl<'a, T: VarInt> VarIntDecoder<'a, T> {
     }
 }
 
+/// Creates an exhausted decoder over an empty buffer, as a placeholder
+impl<T: VarInt> Default for VarIntDecoder<'_, T> {
+    fn default() -> Self {
+        VarIntDecoder::new(&[])
+    }
+}
+
+/// Iterator returned by `VarIntDecoder::iter`, reading through the borrowed decoder
+pub struct VarIntDecoderIter<'d, 'a, T: VarInt = u64> {
+    decoder: &'d mut VarIntDecoder<'a, T>,
+    failed: bool,
+}
+
+impl<T: VarInt> Iterator for VarIntDecoderIter<'_, '_, T> {
+    type Item = Result<T, Error>;
+    
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed || self.decoder.pos >= self.decoder.buf.len() {
+            return None;
+        }
+        
+        let result = self.decoder.read();
+        self.failed = result.is_err();
+        Some(result)
+    }
+}
+
 /// Convenience function to encode a batch of u64 values
 ///
 /// Returns the number of bytes written
@@ -244,4 +633,329 @@ pub fn encode_batch(values: &[u64], buf: &mut [u8]) -> Result<usize, Error> {
 pub fn decode_batch(buf: &[u8], values: &mut [u64]) -> Result<usize, Error> {
     let mut decoder = VarIntDecoder::new(buf);
     decoder.read_batch(values)
-} 
\ No newline at end of file
+}
+
+/// Decodes a batch of values from trusted input with fewer checks per value
+///
+/// The buffer is checked once to end on a complete varint. After that, values
+/// with a full `T::MAX_ENCODED_LEN` bytes left behind them are decoded without
+/// per-byte end-of-input or width checks. This is meant for well-formed input such
+/// as files this crate just wrote: bits beyond the width of `T` are silently
+/// dropped instead of reported. The function stays memory-safe on any input.
+///
+/// Returns the number of values written to `out`
+///
+/// # Errors
+/// * Returns `Error::UnexpectedEof` if the buffer ends with a dangling continuation byte
+/// * Returns `Error::Overflow` if a varint is longer than `T::MAX_ENCODED_LEN` bytes
+pub fn decode_batch_trusted<T: VarInt>(buf: &[u8], out: &mut [T]) -> Result<usize, Error> {
+    // The single outer bound check
+    if let Some(&last) = buf.last() {
+        if last & 0x80 != 0 {
+            return Err(Error::unexpected_eof());
+        }
+    }
+    
+    let max_len = T::MAX_ENCODED_LEN;
+    let mut pos = 0;
+    let mut count = 0;
+    
+    while count < out.len() && pos < buf.len() {
+        if buf.len() - pos >= max_len {
+            // A fixed-length window lets the compiler drop the per-byte bounds checks
+            let window = &buf[pos..pos + max_len];
+            let mut result = T::Unsigned::from_byte(0, 0);
+            let mut i = 0;
+            
+            loop {
+                let byte = window[i];
+                result = result.bitor(T::Unsigned::from_byte(byte & 0x7F, i));
+                i += 1;
+                
+                if byte & 0x80 == 0 {
+                    break;
+                }
+                if i == max_len {
+                    return Err(Error::overflow(i));
+                }
+            }
+            
+            out[count] = T::try_from_unsigned(result)?;
+            pos += i;
+        } else {
+            // Near the end, fall back to the fully checked decoder
+            let (value, bytes_read) = decode::<T>(&buf[pos..])?;
+            out[count] = value;
+            pos += bytes_read;
+        }
+        
+        count += 1;
+    }
+    
+    Ok(count)
+}
+
+/// Returns the total number of bytes a batch of signed values takes with zigzag encoding
+///
+/// Matches what `encode_zigzag_batch` and `VarIntEncoder::write_zigzag_batch` write,
+/// so the buffer can be checked before encoding.
+pub fn zigzag_batch_size<S: ZigZag>(values: &[S]) -> usize {
+    values.iter().map(|&value| zigzag_size(value)).sum()
+}
+
+/// Convenience function to encode a batch of signed values using zigzag encoding
+///
+/// Returns the number of bytes written
+#[inline]
+pub fn encode_zigzag_batch<S: ZigZag>(values: &[S], buf: &mut [u8]) -> Result<usize, Error> {
+    let mut encoder = VarIntEncoder::<S::Unsigned>::new(buf);
+    encoder.write_zigzag_batch(values)
+}
+
+/// Convenience function to decode a batch of zigzag-encoded signed values
+///
+/// Returns the number of values read
+#[inline]
+pub fn decode_zigzag_batch<S: ZigZag>(buf: &[u8], values: &mut [S]) -> Result<usize, Error> {
+    let mut decoder = VarIntDecoder::<S::Unsigned>::new(buf);
+    decoder.read_zigzag_batch(values)
+}
+
+/// Decodes a length-prefixed run of back-to-back varints, like a protobuf packed repeated field
+///
+/// Reads a varint byte length, then decodes values only within that many bytes.
+///
+/// Returns the number of values written to `out`
+///
+/// # Errors
+/// * Returns `Error::InputTooShort` if the declared length exceeds the remaining buffer
+/// * Returns `Error::UnexpectedEof` if the last value is cut off by the declared length
+/// * Returns `Error::BufferTooSmall` if `out` cannot hold all values, with the counts in values
+pub fn decode_packed<T: VarInt>(buf: &[u8], out: &mut [T]) -> Result<usize, Error> {
+    let (len, prefix_size) = decode::<usize>(buf)?;
+    let data = &buf[prefix_size..];
+    
+    if len > data.len() {
+        return Err(Error::input_too_short());
+    }
+    
+    let packed = &data[..len];
+    let count = count_varints(packed)?;
+    
+    if out.len() < count {
+        return Err(Error::buffer_too_small(count, out.len()));
+    }
+    
+    let mut decoder = VarIntDecoder::<T>::new(packed);
+    for slot in &mut out[..count] {
+        *slot = decoder.read()?;
+    }
+    
+    Ok(count)
+}
+
+/// Decodes values up to a delimiter byte that closes the varint region
+///
+/// The delimiter is only recognised at a varint boundary, so it may still
+/// appear inside a value. A value whose first byte equals the delimiter cannot
+/// be stored in the region, e.g. `127 | n << 7` values with a `0xFF` delimiter.
+///
+/// Returns the number of values written to `out` and the number of bytes
+/// consumed, including the delimiter
+///
+/// # Errors
+/// * Returns `Error::UnexpectedEof` if the buffer ends before the delimiter
+/// * Returns `Error::BufferTooSmall` if `out` is full before the delimiter, with the counts in values
+/// * Returns any error from decoding the values themselves
+pub fn decode_until<T: VarInt>(buf: &[u8], delimiter: u8, out: &mut [T]) -> Result<(usize, usize), Error> {
+    let mut pos = 0;
+    let mut count = 0;
+    
+    loop {
+        match buf.get(pos) {
+            None => return Err(Error::unexpected_eof()),
+            Some(&byte) if byte == delimiter => return Ok((count, pos + 1)),
+            Some(_) => {}
+        }
+        
+        if count == out.len() {
+            return Err(Error::buffer_too_small(count + 1, out.len()));
+        }
+        
+        let (value, bytes_read) = decode::<T>(&buf[pos..])?;
+        out[count] = value;
+        pos += bytes_read;
+        count += 1;
+    }
+}
+
+/// Decodes the single varint starting at `offset`
+///
+/// Pairs with the offsets recorded by `VarIntEncoder::write_batch_offsets`
+/// for random access into a packed buffer.
+///
+/// Returns the decoded value and the number of bytes read
+///
+/// # Errors
+/// * Returns `Error::InputTooShort` if `offset` is at or past the end of the buffer
+/// * Returns any error from decoding the value itself
+pub fn read_at<T: VarInt>(buf: &[u8], offset: usize) -> Result<(T, usize), Error> {
+    if offset >= buf.len() {
+        return Err(Error::input_too_short());
+    }
+    
+    decode(&buf[offset..])
+}
+
+/// Checks that a buffer holds only complete varints that fit in `T`
+///
+/// Applies the same checks as `decode` to every value but keeps nothing, so
+/// a received frame can be validated without an output buffer. Unlike
+/// `count_varints`, values that overflow `T` are caught.
+///
+/// Returns the number of values
+///
+/// # Errors
+/// * Returns `Error::UnexpectedEof` if the buffer ends with a truncated varint
+/// * Returns any error from decoding the values themselves
+pub fn validate_stream<T: VarInt>(buf: &[u8]) -> Result<usize, Error> {
+    let mut pos = 0;
+    let mut count = 0;
+    
+    while pos < buf.len() {
+        let (_, bytes_read) = decode::<T>(&buf[pos..])?;
+        pos += bytes_read;
+        count += 1;
+    }
+    
+    Ok(count)
+}
+
+/// Decodes every value in a buffer and folds them into an accumulator in one pass
+///
+/// No values are buffered, so this is the allocation-free way to reduce a
+/// stream, such as taking a maximum or a checksum.
+///
+/// # Errors
+/// * Returns `Error::UnexpectedEof` if the buffer ends with a truncated varint
+/// * Returns the first error from decoding the values themselves
+pub fn fold_values<T: VarInt, A, F: FnMut(A, T) -> A>(buf: &[u8], init: A, mut f: F) -> Result<A, Error> {
+    let mut pos = 0;
+    let mut acc = init;
+    
+    while pos < buf.len() {
+        let (value, bytes_read) = decode::<T>(&buf[pos..])?;
+        acc = f(acc, value);
+        pos += bytes_read;
+    }
+    
+    Ok(acc)
+}
+
+/// Decodes every value in a buffer and sums them, starting from `T::default()`
+///
+/// The addition is plain `+`, so a sum that overflows `T` panics in debug
+/// builds; decode into a wider type, or use `fold_values` with checked
+/// arithmetic, when that can happen.
+///
+/// # Errors
+/// Same as `fold_values`
+pub fn sum_values<T: VarInt + Add<Output = T> + Default>(buf: &[u8]) -> Result<T, Error> {
+    fold_values(buf, T::default(), |acc, value: T| acc + value)
+}
+
+/// Decodes as many values as possible, reporting where decoding stopped
+///
+/// A best-effort parse for logging and recovery: values before a bad or
+/// truncated one are kept in `out` instead of being discarded with the error.
+///
+/// Returns the number of values decoded, and the error with the byte offset of
+/// the value that caused it, or `None` if the whole buffer was decoded. If `out`
+/// fills up before the input is used up, the error is `Error::BufferTooSmall`
+/// with the counts in values.
+pub fn decode_recoverable<T: VarInt>(buf: &[u8], out: &mut [T]) -> (usize, Option<(Error, usize)>) {
+    let mut pos = 0;
+    let mut count = 0;
+    
+    while pos < buf.len() {
+        if count == out.len() {
+            let error = match count_varints(&buf[pos..]) {
+                Ok(undecoded) => Error::buffer_too_small(count + undecoded, out.len()),
+                Err(e) => e,
+            };
+            return (count, Some((error, pos)));
+        }
+        
+        match decode::<T>(&buf[pos..]) {
+            Ok((value, bytes_read)) => {
+                out[count] = value;
+                pos += bytes_read;
+                count += 1;
+            }
+            Err(e) => return (count, Some((e, pos))),
+        }
+    }
+    
+    (count, None)
+}
+
+/// Counts the varints in a buffer without decoding them
+///
+/// Every byte without the continuation bit terminates one varint, so the
+/// result is the same regardless of the integer type that was encoded.
+///
+/// # Errors
+/// Returns `Error::UnexpectedEof` if the buffer ends with a dangling continuation byte
+pub fn count_varints(buf: &[u8]) -> Result<usize, Error> {
+    if let Some(&last) = buf.last() {
+        if last & 0x80 != 0 {
+            return Err(Error::unexpected_eof());
+        }
+    }
+    
+    Ok(buf.iter().filter(|&&byte| byte & 0x80 == 0).count())
+}
+
+/// Counts how many values of a batch encode to each byte length
+///
+/// `histogram[n]` is the number of values that take `n` bytes. Index 0 is
+/// always zero, and the array runs up to `MAX_VARINT_LEN`.
+pub fn size_histogram<T: VarInt>(values: &[T]) -> [usize; MAX_VARINT_LEN + 1] {
+    let mut histogram = [0usize; MAX_VARINT_LEN + 1];
+    for &value in values {
+        histogram[value.varint_size()] += 1;
+    }
+    histogram
+}
+
+/// Encodes a single VarInt value into a newly allocated vector
+///
+/// The vector is sized exactly to `varint_size(value)`
+#[cfg(feature = "alloc")]
+pub fn encode_to_vec<T: VarInt>(value: T) -> Vec<u8> {
+    let mut out = Vec::with_capacity(value.varint_size());
+    out.extend(bytes_of(value));
+    out
+}
+
+/// Encodes a batch of VarInt values into a newly allocated vector
+///
+/// The vector is sized exactly to the sum of `varint_size` over all values
+#[cfg(feature = "alloc")]
+pub fn encode_batch_to_vec<T: VarInt>(values: &[T]) -> Vec<u8> {
+    let total: usize = values.iter().map(|value| value.varint_size()).sum();
+    let mut out = Vec::with_capacity(total);
+    for &value in values {
+        out.extend(bytes_of(value));
+    }
+    out
+}
+
+/// Decodes every VarInt value in the buffer into a newly allocated vector
+///
+/// # Errors
+/// Returns the first error encountered while decoding
+#[cfg(feature = "alloc")]
+pub fn decode_all<T: VarInt>(buf: &[u8]) -> Result<Vec<T>, Error> {
+    values_from(buf).collect()
+}
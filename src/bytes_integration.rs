@@ -0,0 +1,71 @@
+use bytes::{Buf, BufMut};
+use crate::error::Error;
+use crate::traits::{VarInt, VarIntOps};
+use crate::iter::bytes_of;
+use crate::encoding::push_byte;
+
+/// Writes a VarInt value into a `BufMut`, advancing its cursor by the encoded length
+///
+/// # Panics
+/// Panics if the buffer does not have enough remaining capacity, like `BufMut::put_u8`
+pub fn put_varint<B: BufMut, T: VarInt>(buf: &mut B, value: T) {
+    for byte in bytes_of(value) {
+        buf.put_u8(byte);
+    }
+}
+
+/// Reads a VarInt value from a `Buf`, advancing its cursor past the encoded bytes
+///
+/// # Errors
+/// * Returns `Error::InputTooShort` if the buffer is empty
+/// * Returns `Error::UnexpectedEof` if the buffer runs out mid-value
+/// * Returns `Error::Overflow` if overflow occurs during decoding
+pub fn get_varint<B: Buf, T: VarInt>(buf: &mut B) -> Result<T, Error> {
+    let mut result = T::Unsigned::from_byte(0, 0);
+    let mut shift = 0;
+    
+    loop {
+        if !buf.has_remaining() {
+            return Err(if shift == 0 { Error::input_too_short() } else { Error::unexpected_eof() });
+        }
+        
+        if let Some(value) = push_byte(&mut result, buf.get_u8(), &mut shift)? {
+            return Ok(value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encoding::varint_size;
+    
+    #[test]
+    fn test_put_get_round_trip() {
+        let mut storage = [0u8; 32];
+        let mut out: &mut [u8] = &mut storage;
+        put_varint(&mut out, 300u32);
+        put_varint(&mut out, -2i64);
+        put_varint(&mut out, u128::MAX);
+        let written = 32 - out.len();
+        assert_eq!(written, varint_size(300u32) + varint_size(-2i64) + varint_size(u128::MAX));
+        
+        let mut input = &storage[..written];
+        assert_eq!(get_varint::<_, u32>(&mut input), Ok(300));
+        assert_eq!(get_varint::<_, i64>(&mut input), Ok(-2));
+        assert_eq!(get_varint::<_, u128>(&mut input), Ok(u128::MAX));
+        assert!(!input.has_remaining());
+    }
+    
+    #[test]
+    fn test_get_varint_errors() {
+        let mut truncated: &[u8] = &[0x80, 0x80];
+        assert_eq!(get_varint::<_, u64>(&mut truncated), Err(Error::UnexpectedEof));
+        
+        let mut empty: &[u8] = &[];
+        assert_eq!(get_varint::<_, u64>(&mut empty), Err(Error::InputTooShort));
+        
+        let mut overlong: &[u8] = &[0x80, 0x80, 0x80, 0x01];
+        assert_eq!(get_varint::<_, u16>(&mut overlong), Err(Error::Overflow { bytes_read: 3 }));
+    }
+}
@@ -0,0 +1,129 @@
+use crate::error::Error;
+use crate::traits::VarInt;
+use crate::encoding::{encode, decode};
+
+/// Cursor over a borrowed buffer that reads and writes varints at one shared position
+///
+/// Unlike a `VarIntEncoder`/`VarIntDecoder` pair, writing and reading move the
+/// same position, so a round trip is a write, a `seek(0)` and a read. The value
+/// type is chosen per call rather than fixed for the whole cursor.
+pub struct VarIntCursor<'a> {
+    buf: &'a mut [u8],
+    pos: usize,
+}
+
+impl<'a> VarIntCursor<'a> {
+    /// Creates a new cursor at the start of the buffer
+    pub fn new(buf: &'a mut [u8]) -> Self {
+        VarIntCursor { buf, pos: 0 }
+    }
+    
+    /// Gets the current position in the buffer
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+    
+    /// Gets the number of bytes between the position and the end of the buffer
+    pub fn remaining(&self) -> usize {
+        self.buf.len() - self.pos
+    }
+    
+    /// Moves the position to an absolute offset
+    ///
+    /// # Errors
+    /// Returns `Error::BufferTooSmall` if the offset is past the end of the buffer;
+    /// the position is unchanged
+    pub fn seek(&mut self, pos: usize) -> Result<(), Error> {
+        if pos > self.buf.len() {
+            return Err(Error::buffer_too_small(pos, self.buf.len()));
+        }
+        
+        self.pos = pos;
+        Ok(())
+    }
+    
+    /// Writes a varint at the position and moves past it
+    ///
+    /// Returns the number of bytes written
+    ///
+    /// # Errors
+    /// Returns `Error::BufferTooSmall` with the value's size and the remaining space
+    /// if the value does not fit; nothing is written and the position is unchanged
+    pub fn write_varint<T: VarInt>(&mut self, value: T) -> Result<usize, Error> {
+        let needed_size = value.varint_size();
+        let remaining = self.remaining();
+        
+        if needed_size > remaining {
+            return Err(Error::buffer_too_small(needed_size, remaining));
+        }
+        
+        let bytes_written = encode(value, &mut self.buf[self.pos..])?;
+        self.pos += bytes_written;
+        Ok(bytes_written)
+    }
+    
+    /// Reads a varint at the position and moves past it
+    ///
+    /// # Errors
+    /// * Returns `Error::InputTooShort` if the position is at the end of the buffer
+    /// * Returns `Error::UnexpectedEof` if the buffer ends in the middle of a value
+    /// * Returns `Error::Overflow` if the value does not fit in `T`
+    ///
+    /// The position is unchanged on error.
+    pub fn read_varint<T: VarInt>(&mut self) -> Result<T, Error> {
+        let (value, bytes_read) = decode::<T>(&self.buf[self.pos..])?;
+        self.pos += bytes_read;
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_write_seek_read() {
+        let mut buf = [0u8; 32];
+        let mut cursor = VarIntCursor::new(&mut buf);
+        
+        assert_eq!(cursor.write_varint(300u32), Ok(2));
+        assert_eq!(cursor.write_varint(7u8), Ok(1));
+        assert_eq!(cursor.write_varint(u64::MAX), Ok(10));
+        assert_eq!(cursor.position(), 13);
+        
+        cursor.seek(0).unwrap();
+        assert_eq!(cursor.read_varint::<u32>(), Ok(300));
+        assert_eq!(cursor.read_varint::<u8>(), Ok(7));
+        assert_eq!(cursor.read_varint::<u64>(), Ok(u64::MAX));
+        assert_eq!(cursor.position(), 13);
+        
+        // Overwrite the middle value in place
+        cursor.seek(2).unwrap();
+        cursor.write_varint(9u8).unwrap();
+        cursor.seek(2).unwrap();
+        assert_eq!(cursor.read_varint::<u8>(), Ok(9));
+    }
+    
+    #[test]
+    fn test_errors() {
+        let mut buf = [0u8; 4];
+        let mut cursor = VarIntCursor::new(&mut buf);
+        
+        assert_eq!(cursor.seek(5), Err(Error::BufferTooSmall { needed: 5, actual: 4 }));
+        assert_eq!(cursor.position(), 0);
+        
+        cursor.seek(3).unwrap();
+        assert_eq!(cursor.write_varint(300u32), Err(Error::BufferTooSmall { needed: 2, actual: 1 }));
+        assert_eq!(cursor.position(), 3);
+        
+        cursor.seek(4).unwrap();
+        assert_eq!(cursor.read_varint::<u32>(), Err(Error::InputTooShort));
+        
+        // Value too wide for the requested type leaves the position alone
+        cursor.seek(0).unwrap();
+        cursor.write_varint(300u32).unwrap();
+        cursor.seek(0).unwrap();
+        assert_eq!(cursor.read_varint::<u8>(), Err(Error::Overflow { bytes_read: 2 }));
+        assert_eq!(cursor.position(), 0);
+    }
+}
@@ -0,0 +1,72 @@
+use core::fmt;
+
+/// Writes bytes as space-separated `0x..` hex, for inspecting encoded frames
+///
+/// Works with any `core::fmt::Write` sink, such as a serial console or a
+/// fixed-size string, without allocating.
+///
+/// # Parameters
+/// * `buf` - The bytes to print
+/// * `w` - The formatter sink to write into
+pub fn write_hex<W: fmt::Write>(buf: &[u8], w: &mut W) -> fmt::Result {
+    for (i, byte) in buf.iter().enumerate() {
+        if i > 0 {
+            w.write_char(' ')?;
+        }
+        write!(w, "{:#04x}", byte)?;
+    }
+    
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    // Fixed-capacity sink standing in for a console or heapless string
+    struct FixedWriter {
+        buf: [u8; 32],
+        len: usize,
+    }
+    
+    impl fmt::Write for FixedWriter {
+        fn write_str(&mut self, s: &str) -> fmt::Result {
+            let end = self.len + s.len();
+            if end > self.buf.len() {
+                return Err(fmt::Error);
+            }
+            self.buf[self.len..end].copy_from_slice(s.as_bytes());
+            self.len = end;
+            Ok(())
+        }
+    }
+    
+    impl FixedWriter {
+        fn new() -> Self {
+            FixedWriter { buf: [0; 32], len: 0 }
+        }
+        
+        fn as_str(&self) -> &str {
+            core::str::from_utf8(&self.buf[..self.len]).unwrap()
+        }
+    }
+    
+    #[test]
+    fn test_write_hex() {
+        let mut out = FixedWriter::new();
+        write_hex(&[0x80, 0x01], &mut out).unwrap();
+        assert_eq!(out.as_str(), "0x80 0x01");
+        
+        let mut out = FixedWriter::new();
+        write_hex(&[0x05], &mut out).unwrap();
+        assert_eq!(out.as_str(), "0x05");
+        
+        let mut out = FixedWriter::new();
+        write_hex(&[], &mut out).unwrap();
+        assert_eq!(out.as_str(), "");
+        
+        // Errors from the sink are passed through
+        let mut out = FixedWriter::new();
+        assert!(write_hex(&[0xFF; 16], &mut out).is_err());
+    }
+}
@@ -0,0 +1,216 @@
+use alloc::vec::Vec;
+use crate::{encode, decode, varint_size, Error, VarintValue};
+use crate::value::TYPE_BITS_BYTES;
+
+/// Default maximum number of nested sequences accepted by `DynValue::from_bytes`
+pub const DEFAULT_MAX_DEPTH: usize = 32;
+
+// Type byte for sequences; byte strings use TYPE_BITS_BYTES from value.rs.
+// Integers keep the VarintValue type bytes (high 3 bits 000 or 001).
+const TYPE_BITS_SEQ: u8 = 0b011_00000;
+
+/// Self-describing value built on varints, able to hold integers,
+/// raw byte strings and arbitrarily nested sequences.
+///
+/// Every value starts with a type byte:
+/// * `Int` reuses the `VarintValue` type byte, followed by the varint payload
+/// * `Bytes` is followed by a varint length and the raw bytes
+/// * `Seq` is followed by a varint element count and the encoded elements
+#[derive(Debug, Clone, PartialEq)]
+pub enum DynValue<'a> {
+    /// Integer value
+    Int(VarintValue),
+    /// Raw byte string, borrowed from the input when decoding
+    Bytes(&'a [u8]),
+    /// Sequence of nested values
+    Seq(Vec<DynValue<'a>>),
+}
+
+impl<'a> DynValue<'a> {
+    /// Returns the number of bytes needed to serialize this value
+    pub fn serialized_size(&self) -> usize {
+        match self {
+            DynValue::Int(value) => value.framed_size(),
+            DynValue::Bytes(bytes) => 1 + varint_size(bytes.len() as u64) + bytes.len(),
+            DynValue::Seq(items) => {
+                1 + varint_size(items.len() as u64)
+                    + items.iter().map(DynValue::serialized_size).sum::<usize>()
+            }
+        }
+    }
+    
+    /// Serializes the value, including any nested values, into a byte buffer.
+    ///
+    /// # Arguments
+    /// * `buffer` - The buffer to write into
+    ///
+    /// # Returns
+    /// * `Ok(size)` - The number of bytes written
+    /// * `Err(...)` - If the buffer is too small
+    pub fn to_bytes(&self, buffer: &mut [u8]) -> Result<usize, Error> {
+        let needed = self.serialized_size();
+        if buffer.len() < needed {
+            return Err(Error::buffer_too_small(needed, buffer.len()));
+        }
+        
+        self.write_to(buffer)
+    }
+    
+    // Writes the value assuming the buffer has already been size-checked
+    fn write_to(&self, buffer: &mut [u8]) -> Result<usize, Error> {
+        match self {
+            DynValue::Int(value) => value.encode_framed(buffer),
+            DynValue::Bytes(bytes) => {
+                buffer[0] = TYPE_BITS_BYTES;
+                let pos = 1 + encode(bytes.len() as u64, &mut buffer[1..])?;
+                buffer[pos..pos + bytes.len()].copy_from_slice(bytes);
+                Ok(pos + bytes.len())
+            }
+            DynValue::Seq(items) => {
+                buffer[0] = TYPE_BITS_SEQ;
+                let mut pos = 1 + encode(items.len() as u64, &mut buffer[1..])?;
+                for item in items {
+                    pos += item.write_to(&mut buffer[pos..])?;
+                }
+                Ok(pos)
+            }
+        }
+    }
+    
+    /// Deserializes a value from a byte buffer, allowing at most
+    /// `DEFAULT_MAX_DEPTH` levels of nested sequences.
+    ///
+    /// # Arguments
+    /// * `bytes` - The byte buffer to read from
+    ///
+    /// # Returns
+    /// * `Ok((value, size))` - The deserialized value and number of bytes read
+    /// * `Err(...)` - If decoding fails
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<(Self, usize), Error> {
+        Self::from_bytes_with_max_depth(bytes, DEFAULT_MAX_DEPTH)
+    }
+    
+    /// Deserializes a value from a byte buffer with a custom nesting limit.
+    ///
+    /// `max_depth` is the number of sequences that may be nested inside each other;
+    /// a limit of 0 only accepts integers and byte strings.
+    ///
+    /// # Errors
+    /// * Returns `Error::NestingTooDeep` if the input nests deeper than `max_depth`
+    /// * Returns `Error::InputTooShort` if the input ends before the value does
+    /// * Returns `Error::InvalidEncoding` if a type byte is unknown
+    pub fn from_bytes_with_max_depth(bytes: &'a [u8], max_depth: usize) -> Result<(Self, usize), Error> {
+        if bytes.is_empty() {
+            return Err(Error::input_too_short());
+        }
+        
+        let type_byte = bytes[0];
+        let data = &bytes[1..];
+        
+        match type_byte {
+            TYPE_BITS_BYTES => {
+                let (len, prefix_len) = decode::<u64>(data)?;
+                let available = (data.len() - prefix_len) as u64;
+                if len > available {
+                    return Err(Error::input_too_short());
+                }
+                
+                let end = prefix_len + len as usize;
+                Ok((DynValue::Bytes(&data[prefix_len..end]), 1 + end))
+            }
+            TYPE_BITS_SEQ => {
+                if max_depth == 0 {
+                    return Err(Error::nesting_too_deep());
+                }
+                
+                let (count, prefix_len) = decode::<u64>(data)?;
+                let mut pos = 1 + prefix_len;
+                
+                // Every element takes at least one byte, so never reserve more than the input can hold
+                let mut items = Vec::with_capacity(count.min((bytes.len() - pos) as u64) as usize);
+                for _ in 0..count {
+                    let (item, bytes_read) = Self::from_bytes_with_max_depth(&bytes[pos..], max_depth - 1)?;
+                    items.push(item);
+                    pos += bytes_read;
+                }
+                
+                Ok((DynValue::Seq(items), pos))
+            }
+            _ => {
+                let (value, bytes_read) = VarintValue::decode_payload(type_byte, data)?;
+                Ok((DynValue::Int(value), 1 + bytes_read))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+    use crate::varint;
+    
+    #[test]
+    fn test_nested_round_trip() {
+        let value = DynValue::Seq(vec![
+            DynValue::Int(varint!(u32: 42)),
+            DynValue::Int(varint!(i8: 0)),
+            DynValue::Bytes(b"hello"),
+            DynValue::Seq(vec![
+                DynValue::Int(varint!(i64: -1000000)),
+                DynValue::Seq(vec![]),
+                DynValue::Bytes(&[]),
+            ]),
+            DynValue::Int(varint!(u128: u128::MAX)),
+        ]);
+        
+        let mut buffer = [0u8; 64];
+        let bytes_written = value.to_bytes(&mut buffer).unwrap();
+        assert_eq!(bytes_written, value.serialized_size());
+        
+        let (decoded, bytes_read) = DynValue::from_bytes(&buffer[..bytes_written]).unwrap();
+        assert_eq!(decoded, value);
+        assert_eq!(bytes_read, bytes_written);
+    }
+    
+    #[test]
+    fn test_depth_limit() {
+        let mut value = DynValue::Int(varint!(u8: 1));
+        for _ in 0..4 {
+            value = DynValue::Seq(vec![value]);
+        }
+        
+        let mut buffer = [0u8; 32];
+        let bytes_written = value.to_bytes(&mut buffer).unwrap();
+        let encoded = &buffer[..bytes_written];
+        
+        assert!(DynValue::from_bytes_with_max_depth(encoded, 4).is_ok());
+        assert_eq!(DynValue::from_bytes_with_max_depth(encoded, 3), Err(Error::NestingTooDeep));
+        
+        // A long run of nested sequence headers must be rejected, not overflow the stack
+        let mut malicious = vec![0u8; 2 * 100_000];
+        for pair in malicious.chunks_mut(2) {
+            pair.copy_from_slice(&[TYPE_BITS_SEQ, 1]);
+        }
+        assert_eq!(DynValue::from_bytes(&malicious), Err(Error::NestingTooDeep));
+    }
+    
+    #[test]
+    fn test_error_handling() {
+        let value = DynValue::Bytes(b"payload");
+        let mut small_buffer = [0u8; 4];
+        assert_eq!(
+            value.to_bytes(&mut small_buffer),
+            Err(Error::BufferTooSmall { needed: 9, actual: 4 })
+        );
+        
+        // Byte string length exceeding the input
+        assert_eq!(DynValue::from_bytes(&[TYPE_BITS_BYTES, 5, 1, 2]), Err(Error::InputTooShort));
+        
+        // Sequence with fewer elements than announced
+        assert_eq!(DynValue::from_bytes(&[TYPE_BITS_SEQ, 2, 0x00, 0x01]), Err(Error::InputTooShort));
+        
+        // Unknown type byte
+        assert_eq!(DynValue::from_bytes(&[0xFF, 0x00]), Err(Error::InvalidEncoding { offset: 0 }));
+    }
+}
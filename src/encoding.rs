@@ -2,6 +2,11 @@
 use crate::error::Error;
 use crate::traits::{VarInt, VarIntOps};
 
+/// Largest possible varint encoding, a `u128` (or a zigzag-encoded `i128`)
+///
+/// A buffer of this size holds any single value the crate encodes.
+pub const MAX_VARINT_LEN: usize = 19;
+
 /// Encodes arbitrary VarInt type to varint format
 ///
 /// Returns the number of bytes written
@@ -17,10 +22,7 @@ pub fn encode<T: VarInt>(value: T, buf: &mut [u8]) -> Result<usize, Error> {
     let needed_size = value.varint_size();
     
     if buf.len() < needed_size {
-        return Err(Error::BufferTooSmall {
-            needed: needed_size,
-            actual: buf.len(),
-        });
+        return Err(Error::buffer_too_small(needed_size, buf.len()));
     }
     
     let mut i = 0;
@@ -34,11 +36,98 @@ pub fn encode<T: VarInt>(value: T, buf: &mut [u8]) -> Result<usize, Error> {
         buf[i] = val.get_final_byte();
         Ok(i + 1)
     } else {
-        Err(Error::BufferTooSmall {
-            needed: i + 1,
-            actual: buf.len(),
-        })
+        Err(Error::buffer_too_small(i + 1, buf.len()))
+    }
+}
+
+/// Encodes arbitrary VarInt type starting at `offset` in the buffer
+///
+/// Saves slicing `&mut buf[offset..]` by hand when appending to a shared buffer.
+///
+/// Returns the number of bytes written, not the new offset
+///
+/// # Parameters
+/// * `value` - The value to encode
+/// * `buf` - Output buffer
+/// * `offset` - Index of the first byte to write
+///
+/// # Errors
+/// Returns `Error::BufferTooSmall` with the end of the value and the buffer length
+/// if the value does not fit after `offset`; nothing is written
+pub fn encode_at<T: VarInt>(value: T, buf: &mut [u8], offset: usize) -> Result<usize, Error> {
+    let end = offset.saturating_add(value.varint_size());
+    if end > buf.len() {
+        return Err(Error::buffer_too_small(end, buf.len()));
+    }
+    
+    encode(value, &mut buf[offset..end])
+}
+
+/// Encodes arbitrary VarInt type into a stack array sized for the largest varint
+///
+/// Returns the array and the number of bytes used; the encoding is `&array[..len]`.
+/// The array always fits, so there is no error path.
+///
+/// # Parameters
+/// * `value` - The value to encode
+#[inline]
+pub fn encode_array<T: VarInt>(value: T) -> ([u8; MAX_VARINT_LEN], usize) {
+    let mut buf = [0u8; MAX_VARINT_LEN];
+    let mut val = value.to_unsigned();
+    let mut i = 0;
+    
+    while val.needs_another_byte() {
+        buf[i] = val.get_byte_with_continuation();
+        val = val.shift_right_7();
+        i += 1;
     }
+    buf[i] = val.get_final_byte();
+    
+    (buf, i + 1)
+}
+
+/// Encodes a u64 at compile time, for varint tables baked into the binary
+///
+/// Returns the array and the number of bytes used, like `encode_array`, so a
+/// `static TABLE: [([u8; 10], usize); N]` can be built without runtime cost.
+pub const fn encode_u64_const(value: u64) -> ([u8; 10], usize) {
+    let mut buf = [0u8; 10];
+    let mut val = value;
+    let mut i = 0;
+    
+    while val >= 0x80 {
+        buf[i] = (val as u8) | 0x80;
+        val >>= 7;
+        i += 1;
+    }
+    buf[i] = val as u8;
+    
+    (buf, i + 1)
+}
+
+/// Encodes arbitrary VarInt type, refusing values longer than `max_len` bytes
+///
+/// The size is checked before anything is written, so a rejected value leaves
+/// the buffer untouched.
+///
+/// Returns the number of bytes written
+///
+/// # Parameters
+/// * `value` - The value to encode
+/// * `buf` - Output buffer
+/// * `max_len` - Maximum number of bytes the encoding may take
+///
+/// # Errors
+/// * Returns `Error::ValueTooLarge` if the encoding needs more than `max_len` bytes
+/// * Returns `Error::BufferTooSmall` if the buffer is too small
+pub fn encode_max_len<T: VarInt>(value: T, buf: &mut [u8], max_len: usize) -> Result<usize, Error> {
+    let size = value.varint_size();
+    
+    if size > max_len {
+        return Err(Error::value_too_large(size, max_len));
+    }
+    
+    encode(value, buf)
 }
 
 /// Decodes arbitrary VarInt type from varint format
@@ -49,38 +138,330 @@ pub fn encode<T: VarInt>(value: T, buf: &mut [u8]) -> Result<usize, Error> {
 /// * `buf` - Input buffer containing varint encoding
 ///
 /// # Errors
-/// * Returns `Error::InputTooShort` if the input buffer is insufficient
-/// * Returns `Error::InvalidEncoding` if the varint encoding is invalid
-/// * Returns `Error::Overflow` if overflow occurs during decoding
+/// * Returns `Error::InputTooShort` if the input buffer is empty
+/// * Returns `Error::UnexpectedEof` if the input ends after a byte with the continuation bit set
+/// * Returns `Error::InvalidEncoding` if the value cannot be represented in `T`
+/// * Returns `Error::Overflow` if the encoded value does not fit in `T`
 pub fn decode<T: VarInt>(buf: &[u8]) -> Result<(T, usize), Error> {
     let mut result = T::Unsigned::from_byte(0, 0);
     let mut shift = 0;
-    let mut i = 0;
     
-    loop {
-        if i >= buf.len() {
-            return Err(Error::InputTooShort);
+    for &byte in buf {
+        match push_group(&mut result, byte, &mut shift) {
+            Ok(true) => return Ok((T::try_from_unsigned(result)?, shift + 1)),
+            Ok(false) => {}
+            Err(bytes_read) => return Err(Error::overflow(bytes_read)),
         }
+    }
+    
+    // Distinguish an empty buffer from one cut off mid-value
+    Err(if shift == 0 { Error::input_too_short() } else { Error::unexpected_eof() })
+}
+
+/// Decodes a varint at the width `Wide`, then converts it to the narrower `Narrow`
+///
+/// The wire format does not record the width a value was encoded with, so
+/// this makes a width mismatch explicit instead of relying on `decode::<Narrow>`.
+///
+/// Returns the converted value and the number of bytes read
+///
+/// # Errors
+/// * Returns `Error::Overflow` if the value does not fit in `Narrow`, with `bytes_read` covering the whole value
+/// * Returns any error from decoding at the `Wide` width
+pub fn decode_narrowing<Wide: VarInt, Narrow: TryFrom<Wide>>(buf: &[u8]) -> Result<(Narrow, usize), Error> {
+    let (wide, bytes_read) = decode::<Wide>(buf)?;
+    
+    match Narrow::try_from(wide) {
+        Ok(value) => Ok((value, bytes_read)),
+        Err(_) => Err(Error::overflow(bytes_read)),
+    }
+}
+
+/// Decodes a varint, clamping values too large for `T` to its largest encoding
+///
+/// Meant for lossy pipelines such as telemetry, where one corrupt reading
+/// should not drop a whole frame. The trade-off is that a clamped value is
+/// indistinguishable from a genuine maximum, and over-long encodings that
+/// `decode` rejects as malformed are accepted; use `decode` when corruption
+/// must be detected.
+///
+/// The clamp is to all ones in `T::Unsigned`, converted back through
+/// `try_from_unsigned`. That is `T::MAX` for unsigned types, but signed types
+/// read it as the two's complement bits of -1, so use this with unsigned types.
+///
+/// Returns the value and the number of bytes read, which covers the whole
+/// varint even when it was clamped
+///
+/// # Errors
+/// * Returns `Error::InputTooShort` if the buffer is empty
+/// * Returns `Error::UnexpectedEof` if the buffer ends before the varint does
+pub fn decode_saturating<T: VarInt>(buf: &[u8]) -> Result<(T, usize), Error> {
+    match decode::<T>(buf) {
+        Err(Error::Overflow { .. }) => {
+            // Skip to the end of the value so the caller can carry on after it
+            match buf.iter().position(|&byte| byte & 0x80 == 0) {
+                Some(last) => Ok((T::try_from_unsigned(<T::Unsigned as VarIntOps>::MAX)?, last + 1)),
+                None => Err(Error::unexpected_eof()),
+            }
+        }
+        result => result,
+    }
+}
+
+/// Decodes arbitrary VarInt type, returning the rest of the buffer after it
+///
+/// Convenient for sequential parsing: `let (value, rest) = decode_split(rest)?;`
+///
+/// # Errors
+/// Same as `decode`
+#[inline]
+pub fn decode_split<T: VarInt>(buf: &[u8]) -> Result<(T, &[u8]), Error> {
+    let (value, bytes_read) = decode(buf)?;
+    Ok((value, &buf[bytes_read..]))
+}
+
+/// Decodes arbitrary VarInt type after skipping any leading `sentinel` bytes
+///
+/// For framings that mark the start of each frame with a fixed byte. Every
+/// leading byte equal to `sentinel` is skipped, so a value whose encoding
+/// starts with the sentinel (zero, for a `0x00` sentinel) cannot come first.
+///
+/// Returns the decoded value and the number of bytes read, including the skipped sentinels
+///
+/// # Errors
+/// * Returns `Error::InputTooShort` if the buffer is empty or holds only sentinels
+/// * Returns any other error from `decode`
+pub fn decode_after_sentinel<T: VarInt>(buf: &[u8], sentinel: u8) -> Result<(T, usize), Error> {
+    let skipped = buf.iter().take_while(|&&byte| byte == sentinel).count();
+    let (value, bytes_read) = decode(&buf[skipped..])?;
+    Ok((value, skipped + bytes_read))
+}
+
+/// Decodes arbitrary VarInt type from an iterator of bytes
+///
+/// Bytes are pulled until the continuation bit clears, leaving the iterator
+/// just past the varint. This is the pull-based counterpart of `bytes_of`.
+///
+/// # Parameters
+/// * `iter` - Source of encoded bytes
+///
+/// # Errors
+/// * Returns `Error::InputTooShort` if the iterator is empty
+/// * Returns `Error::UnexpectedEof` if the iterator runs out after a byte with the continuation bit set
+/// * Returns `Error::Overflow` if the encoded value does not fit in `T`
+pub fn decode_from_iter<T: VarInt, I: Iterator<Item = u8>>(iter: &mut I) -> Result<T, Error> {
+    let mut result = T::Unsigned::from_byte(0, 0);
+    let mut shift = 0;
+    
+    loop {
+        let byte = match iter.next() {
+            Some(byte) => byte,
+            None if shift == 0 => return Err(Error::input_too_short()),
+            None => return Err(Error::unexpected_eof()),
+        };
         
-        let byte = buf[i];
-        i += 1;
+        if let Some(value) = push_byte(&mut result, byte, &mut shift)? {
+            return Ok(value);
+        }
+    }
+}
+
+/// Decodes arbitrary VarInt type, reading at most `max_bytes` bytes
+///
+/// Returns the decoded value and the number of bytes read
+///
+/// # Parameters
+/// * `buf` - Input buffer containing varint encoding
+/// * `max_bytes` - Maximum number of bytes the encoding may occupy
+///
+/// # Errors
+/// * Returns `Error::LimitExceeded` if the encoding is longer than `max_bytes`
+/// * Returns `Error::InputTooShort` or `Error::UnexpectedEof` if the input buffer is insufficient
+/// * Returns `Error::Overflow` if the encoded value does not fit in `T`
+pub fn decode_limited<T: VarInt>(buf: &[u8], max_bytes: usize) -> Result<(T, usize), Error> {
+    let limited = &buf[..buf.len().min(max_bytes)];
+    
+    match decode::<T>(limited) {
+        Err(Error::InputTooShort | Error::UnexpectedEof) if buf.len() > max_bytes => Err(Error::limit_exceeded()),
+        result => result,
+    }
+}
+
+/// Checks that the 7 payload bits of the byte at group `shift` fit within the type width
+#[inline(always)]
+pub(crate) fn byte_fits<U: VarIntOps>(byte: u8, shift: usize) -> bool {
+    let used = shift * 7;
+    if used >= U::BITS {
+        // Nothing fits past the type width; avoids underflowing the shift below
+        return byte & 0x7F == 0;
+    }
+    used + 7 <= U::BITS || (byte & 0x7F) >> (U::BITS - used) == 0
+}
+
+/// Adds one 7-bit group to a partially decoded value
+///
+/// This is the shared step of every byte-at-a-time decoder, so the overflow
+/// guard lives in one place. `acc` and `shift` carry the partial value between
+/// calls and start at zero. Returns `Ok(true)` once the continuation bit clears,
+/// `Ok(false)` if more bytes are needed, or `Err(bytes_read)` if the value does
+/// not fit in `U`. The error stays a plain count so slice decoding keeps
+/// unrolling; `push_byte` turns it into `Error::Overflow`.
+#[inline(always)]
+pub(crate) fn push_group<U: VarIntOps>(acc: &mut U, byte: u8, shift: &mut usize) -> Result<bool, usize> {
+    // Reject bits beyond the width of the target type
+    if !byte_fits::<U>(byte, *shift) {
+        return Err(*shift + 1);
+    }
+    
+    *acc = acc.bitor(U::from_byte(byte & 0x7F, *shift));
+    
+    // Check if done
+    if byte & 0x80 == 0 {
+        return Ok(true);
+    }
+    
+    *shift += 1;
+    
+    // Prevent too large varint
+    if *shift > U::BITS / 7 {
+        return Err(*shift);
+    }
+    
+    Ok(false)
+}
+
+/// Feeds one byte into a byte-at-a-time decode
+///
+/// `acc` and `shift` carry the partial value between calls and start at zero.
+/// Returns `Some(value)` once the continuation bit clears, or `None` if more
+/// bytes are needed.
+#[inline(always)]
+pub(crate) fn push_byte<T: VarInt>(acc: &mut T::Unsigned, byte: u8, shift: &mut usize) -> Result<Option<T>, Error> {
+    match push_group(acc, byte, shift) {
+        Ok(true) => T::try_from_unsigned(*acc).map(Some),
+        Ok(false) => Ok(None),
+        Err(bytes_read) => Err(Error::overflow(bytes_read)),
+    }
+}
+
+/// Decodes arbitrary VarInt type, accepting only the canonical (shortest) encoding
+///
+/// Returns the decoded value and the number of bytes read
+///
+/// Unlike `decode`, overlong encodings such as `[0x80, 0x00]` for zero are rejected.
+///
+/// # Parameters
+/// * `buf` - Input buffer containing varint encoding
+///
+/// # Errors
+/// * Returns `Error::InputTooShort` or `Error::UnexpectedEof` if the input buffer is insufficient
+/// * Returns `Error::InvalidEncoding` if the encoding is longer than necessary
+/// * Returns `Error::Overflow` if overflow occurs during decoding
+pub fn decode_canonical<T: VarInt>(buf: &[u8]) -> Result<(T, usize), Error> {
+    let (value, bytes_read) = decode::<T>(buf)?;
+    
+    // The first byte past the minimal length is the redundant one
+    let minimal_size = value.varint_size();
+    if bytes_read != minimal_size {
+        return Err(Error::invalid_encoding(minimal_size));
+    }
+    
+    Ok((value, bytes_read))
+}
+
+/// Order of the 7-bit groups in a varint
+///
+/// In both orders every byte but the last has the continuation bit set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Endianness {
+    /// Least significant group first, as written by `encode`
+    #[default]
+    Little,
+    /// Most significant group first
+    Big,
+}
+
+/// Encodes arbitrary VarInt type with the given group order
+///
+/// `Endianness::Little` matches `encode`.
+///
+/// Returns the number of bytes written
+///
+/// # Parameters
+/// * `value` - The value to encode
+/// * `buf` - Output buffer
+/// * `endian` - Group order to write
+///
+/// # Errors
+/// Returns `Error::BufferTooSmall` if the buffer is too small
+pub fn encode_with_endian<T: VarInt>(value: T, buf: &mut [u8], endian: Endianness) -> Result<usize, Error> {
+    let bytes_written = encode(value, buf)?;
+    
+    if endian == Endianness::Big && bytes_written > 1 {
+        // Reverse the groups, then move the continuation bits back onto all but the last byte
+        let groups = &mut buf[..bytes_written];
+        groups.reverse();
+        groups[0] |= 0x80;
+        groups[bytes_written - 1] &= 0x7F;
+    }
+    
+    Ok(bytes_written)
+}
+
+/// Decodes arbitrary VarInt type written with the given group order
+///
+/// `Endianness::Little` matches `decode`. For `Endianness::Big` the terminating
+/// byte is located first, since the position of each group depends on the
+/// total length.
+///
+/// Returns the decoded value and the number of bytes read
+///
+/// # Errors
+/// * Returns `Error::InputTooShort` if the input buffer is empty
+/// * Returns `Error::UnexpectedEof` if the input ends after a byte with the continuation bit set
+/// * Returns `Error::InvalidEncoding` if the value cannot be represented in `T`
+/// * Returns `Error::Overflow` if the encoded value does not fit in `T`
+pub fn decode_with_endian<T: VarInt>(buf: &[u8], endian: Endianness) -> Result<(T, usize), Error> {
+    if endian == Endianness::Little {
+        return decode(buf);
+    }
+    
+    let max_groups = T::Unsigned::BITS / 7 + 1;
+    let mut len = 0;
+    
+    // Find the terminating byte
+    loop {
+        if len >= buf.len() {
+            return Err(if len == 0 { Error::input_too_short() } else { Error::unexpected_eof() });
+        }
         
-        result = result.bitor(T::Unsigned::from_byte(byte & 0x7F, shift));
+        let byte = buf[len];
+        len += 1;
         
-        // Check if done
         if byte & 0x80 == 0 {
             break;
         }
         
-        shift += 1;
-        
         // Prevent too large varint
-        if shift >= T::Unsigned::BITS / 7 + 1 {
-            return Err(Error::Overflow);
+        if len >= max_groups {
+            return Err(Error::overflow(len));
+        }
+    }
+    
+    // Feed the groups least significant first, moving the continuation bits
+    // so they read as a little-endian varint
+    let mut result = T::Unsigned::from_byte(0, 0);
+    let mut shift = 0;
+    for i in (0..len).rev() {
+        let group = if i == 0 { buf[0] & 0x7F } else { buf[i] | 0x80 };
+        
+        // The groups were read out of order, so report the whole value as scanned
+        if push_group(&mut result, group, &mut shift).map_err(|_| Error::overflow(len))? {
+            break;
         }
     }
     
-    Ok((T::from_unsigned(result), i))
+    Ok((T::try_from_unsigned(result)?, len))
 }
 
 /// Calculates the number of bytes needed to encode a VarInt value
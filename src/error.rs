@@ -1,5 +1,8 @@
 /// Error type for varint encoding/decoding operations
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+///
+/// Errors order by variant in declaration order, then by their fields, so
+/// they can key a `BTreeMap` as well as a `HashMap`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum Error {
     /// Output buffer is too small to hold the encoded data
     BufferTooSmall {
@@ -9,11 +12,34 @@ pub enum Error {
         actual: usize,
     },
     /// Overflow error encountered during decoding
-    Overflow,
-    /// Input data is insufficient during decoding
+    Overflow {
+        /// Number of bytes of the value scanned before giving up, including the offending byte
+        bytes_read: usize,
+    },
+    /// Input was empty where a value was expected
     InputTooShort,
     /// Invalid varint encoding encountered during decoding
-    InvalidEncoding,
+    InvalidEncoding {
+        /// Byte offset where the problem was detected, relative to the start of the value being decoded
+        offset: usize,
+    },
+    /// Nested data exceeded the maximum allowed depth during decoding
+    NestingTooDeep,
+    /// Value has a different type than the one requested
+    TypeMismatch,
+    /// Decoding would read more bytes than the caller-supplied limit
+    LimitExceeded,
+    /// Input ended mid-varint, after a byte with the continuation bit set
+    UnexpectedEof,
+    /// Bytes remained after the expected values were decoded
+    TrailingData,
+    /// Encoded value would be longer than the caller-supplied limit
+    ValueTooLarge {
+        /// Number of bytes the encoding needs
+        size: usize,
+        /// Maximum number of bytes allowed
+        max_len: usize,
+    },
 }
 
 // Helper methods for the Error error type
@@ -33,4 +59,97 @@ impl Error {
             _ => None,
         }
     }
-} 
\ No newline at end of file
+    
+    /// Get the number of bytes scanned before an overflow was detected
+    ///
+    /// Useful for skipping past a corrupt value when resynchronizing a stream
+    pub fn bytes_read(&self) -> Option<usize> {
+        match self {
+            Error::Overflow { bytes_read } => Some(*bytes_read),
+            _ => None,
+        }
+    }
+    
+    /// Get the byte offset of invalid data, relative to the start of the value being decoded
+    pub fn offset(&self) -> Option<usize> {
+        match self {
+            Error::InvalidEncoding { offset } => Some(*offset),
+            _ => None,
+        }
+    }
+}
+
+// Out-of-line constructors for the error paths, so the hot encode/decode loops
+// stay compact and the optimizer treats failures as unlikely
+impl Error {
+    /// Build a `BufferTooSmall` error
+    #[cold]
+    #[inline(never)]
+    pub(crate) fn buffer_too_small(needed: usize, actual: usize) -> Self {
+        Error::BufferTooSmall { needed, actual }
+    }
+    
+    /// Build an `InputTooShort` error
+    #[cold]
+    #[inline(never)]
+    pub(crate) fn input_too_short() -> Self {
+        Error::InputTooShort
+    }
+    
+    /// Build an `UnexpectedEof` error
+    #[cold]
+    #[inline(never)]
+    pub(crate) fn unexpected_eof() -> Self {
+        Error::UnexpectedEof
+    }
+    
+    /// Build an `Overflow` error
+    #[cold]
+    #[inline(never)]
+    pub(crate) fn overflow(bytes_read: usize) -> Self {
+        Error::Overflow { bytes_read }
+    }
+    
+    /// Build an `InvalidEncoding` error
+    #[cold]
+    #[inline(never)]
+    pub(crate) fn invalid_encoding(offset: usize) -> Self {
+        Error::InvalidEncoding { offset }
+    }
+    
+    /// Build a `NestingTooDeep` error
+    #[cfg(feature = "alloc")]
+    #[cold]
+    #[inline(never)]
+    pub(crate) fn nesting_too_deep() -> Self {
+        Error::NestingTooDeep
+    }
+    
+    /// Build a `TypeMismatch` error
+    #[cold]
+    #[inline(never)]
+    pub(crate) fn type_mismatch() -> Self {
+        Error::TypeMismatch
+    }
+    
+    /// Build a `LimitExceeded` error
+    #[cold]
+    #[inline(never)]
+    pub(crate) fn limit_exceeded() -> Self {
+        Error::LimitExceeded
+    }
+    
+    /// Build a `TrailingData` error
+    #[cold]
+    #[inline(never)]
+    pub(crate) fn trailing_data() -> Self {
+        Error::TrailingData
+    }
+    
+    /// Build a `ValueTooLarge` error
+    #[cold]
+    #[inline(never)]
+    pub(crate) fn value_too_large(size: usize, max_len: usize) -> Self {
+        Error::ValueTooLarge { size, max_len }
+    }
+}
@@ -0,0 +1,221 @@
+use crate::error::Error;
+use crate::encoding::{encode, decode, varint_size};
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+
+/// Encodes a byte slice as a varint length prefix followed by the raw bytes
+///
+/// Returns the total number of bytes written
+///
+/// # Parameters
+/// * `data` - The payload to write
+/// * `buf` - Output buffer
+///
+/// # Errors
+/// Returns `Error::BufferTooSmall` if the buffer cannot hold the prefix and payload
+pub fn encode_bytes(data: &[u8], buf: &mut [u8]) -> Result<usize, Error> {
+    let prefix_size = varint_size(data.len());
+    let needed_size = prefix_size + data.len();
+    
+    if buf.len() < needed_size {
+        return Err(Error::buffer_too_small(needed_size, buf.len()));
+    }
+    
+    encode(data.len(), buf)?;
+    buf[prefix_size..needed_size].copy_from_slice(data);
+    Ok(needed_size)
+}
+
+/// Decodes a varint length-prefixed byte slice
+///
+/// Returns the payload, borrowed from the input, and the total number of bytes consumed
+///
+/// # Parameters
+/// * `buf` - Input buffer starting with the length prefix
+///
+/// # Errors
+/// * Returns `Error::InputTooShort` if the declared length exceeds the remaining buffer
+/// * Returns `Error::Overflow` if the length prefix does not fit in a `usize`
+pub fn decode_bytes(buf: &[u8]) -> Result<(&[u8], usize), Error> {
+    let (len, prefix_size) = decode::<usize>(buf)?;
+    let data = &buf[prefix_size..];
+    
+    if len > data.len() {
+        return Err(Error::input_too_short());
+    }
+    
+    Ok((&data[..len], prefix_size + len))
+}
+
+/// Encodes a string as a varint length prefix followed by its UTF-8 bytes
+///
+/// Returns the total number of bytes written
+///
+/// # Errors
+/// Returns `Error::BufferTooSmall` if the buffer cannot hold the prefix and bytes
+pub fn encode_str(s: &str, buf: &mut [u8]) -> Result<usize, Error> {
+    encode_bytes(s.as_bytes(), buf)
+}
+
+/// Decodes a varint length-prefixed UTF-8 string
+///
+/// Returns the string, borrowed from the input, and the total number of bytes consumed
+///
+/// # Errors
+/// * Returns `Error::InputTooShort` if the declared length exceeds the remaining buffer
+/// * Returns `Error::InvalidEncoding` if the bytes are not valid UTF-8
+pub fn decode_str(buf: &[u8]) -> Result<(&str, usize), Error> {
+    let (data, bytes_read) = decode_bytes(buf)?;
+    let prefix_size = bytes_read - data.len();
+    let s = core::str::from_utf8(data)
+        .map_err(|e| Error::invalid_encoding(prefix_size + e.valid_up_to()))?;
+    Ok((s, bytes_read))
+}
+
+/// Decodes a varint length-prefixed UTF-8 string into an owned `String`
+///
+/// Returns the string and the total number of bytes consumed
+///
+/// # Errors
+/// * Returns `Error::InputTooShort` if the declared length exceeds the remaining buffer
+/// * Returns `Error::InvalidEncoding` if the bytes are not valid UTF-8
+#[cfg(feature = "alloc")]
+pub fn decode_string(buf: &[u8]) -> Result<(String, usize), Error> {
+    decode_str(buf).map(|(s, bytes_read)| (String::from(s), bytes_read))
+}
+
+/// Writes a varint length prefix directly in front of a body that is already in place
+///
+/// The body must occupy the last `body_len` bytes of `buf`, with space reserved in
+/// front of it for the prefix. Since the prefix size depends on the length, reserve
+/// the worst case (`<usize as VarInt>::MAX_ENCODED_LEN` bytes) before writing a body
+/// of unknown size, then call this once the length is known.
+///
+/// Returns the offset where the prefix starts; the framed message is `&buf[start..]`
+///
+/// # Parameters
+/// * `buf` - Reserved space followed by the body
+/// * `body_len` - Length of the body at the end of `buf`
+///
+/// # Errors
+/// Returns `Error::BufferTooSmall` if the reserved space cannot hold the prefix
+pub fn prepend_length(buf: &mut [u8], body_len: usize) -> Result<usize, Error> {
+    let prefix_size = varint_size(body_len);
+    let needed_size = prefix_size + body_len;
+    
+    if buf.len() < needed_size {
+        return Err(Error::buffer_too_small(needed_size, buf.len()));
+    }
+    
+    let start = buf.len() - needed_size;
+    encode(body_len, &mut buf[start..start + prefix_size])?;
+    Ok(start)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::VarInt;
+    
+    #[test]
+    fn test_empty_payload() {
+        let mut buf = [0xAAu8; 4];
+        assert_eq!(encode_bytes(&[], &mut buf), Ok(1));
+        assert_eq!(buf[0], 0x00);
+        assert_eq!(decode_bytes(&buf[..1]), Ok((&[][..], 1)));
+    }
+    
+    #[test]
+    fn test_two_byte_length_prefix() {
+        let payload = [0x5Au8; 200];
+        let mut buf = [0u8; 210];
+        
+        let bytes_written = encode_bytes(&payload, &mut buf).unwrap();
+        assert_eq!(bytes_written, 202);
+        assert_eq!(&buf[..2], &[0xC8, 0x01]);
+        
+        let (decoded, bytes_read) = decode_bytes(&buf).unwrap();
+        assert_eq!(decoded, &payload[..]);
+        assert_eq!(bytes_read, bytes_written);
+    }
+    
+    #[test]
+    fn test_errors() {
+        // Declared length longer than the remaining input
+        assert_eq!(decode_bytes(&[0x05, 1, 2, 3]), Err(Error::InputTooShort));
+        // Truncated length prefix
+        assert_eq!(decode_bytes(&[0x80]), Err(Error::UnexpectedEof));
+        
+        let mut small = [0u8; 3];
+        assert_eq!(
+            encode_bytes(b"abc", &mut small),
+            Err(Error::BufferTooSmall { needed: 4, actual: 3 })
+        );
+    }
+    
+    #[test]
+    fn test_prepend_length() {
+        const RESERVED: usize = <usize as VarInt>::MAX_ENCODED_LEN;
+        let mut storage = [0u8; RESERVED + 200];
+        
+        // 127 is the longest body with a one-byte prefix, 128 needs two
+        for (body_len, prefix_size) in [(0, 1), (127, 1), (128, 2), (200, 2)] {
+            storage[RESERVED..RESERVED + body_len].fill(0x5A);
+            let framed = &mut storage[..RESERVED + body_len];
+            
+            let start = prepend_length(framed, body_len).unwrap();
+            assert_eq!(start, RESERVED - prefix_size);
+            
+            let (body, bytes_read) = decode_bytes(&framed[start..]).unwrap();
+            assert_eq!(body.len(), body_len);
+            assert!(body.iter().all(|&byte| byte == 0x5A));
+            assert_eq!(bytes_read, framed.len() - start);
+        }
+        
+        // Not enough room reserved for a two-byte prefix
+        let mut tight = [0u8; 129];
+        assert_eq!(
+            prepend_length(&mut tight, 128),
+            Err(Error::BufferTooSmall { needed: 130, actual: 129 })
+        );
+    }
+    
+    #[test]
+    fn test_str_round_trip() {
+        let mut buf = [0u8; 16];
+        
+        let bytes_written = encode_str("hello", &mut buf).unwrap();
+        assert_eq!(&buf[..bytes_written], b"\x05hello");
+        assert_eq!(decode_str(&buf[..bytes_written]), Ok(("hello", 6)));
+        
+        // Length counts UTF-8 bytes, not chars
+        let bytes_written = encode_str("café", &mut buf).unwrap();
+        assert_eq!(buf[0], 5);
+        assert_eq!(decode_str(&buf[..bytes_written]), Ok(("café", 6)));
+        
+        assert_eq!(encode_str("", &mut buf), Ok(1));
+        assert_eq!(decode_str(&buf[..1]), Ok(("", 1)));
+    }
+    
+    #[test]
+    fn test_str_rejects_invalid_utf8() {
+        assert_eq!(decode_str(&[0x02, 0xC3, 0x28]), Err(Error::InvalidEncoding { offset: 1 }));
+        assert_eq!(decode_str(&[0x01, 0xFF]), Err(Error::InvalidEncoding { offset: 1 }));
+        // A multibyte char cut short by the declared length
+        assert_eq!(decode_str(&[0x04, b'c', b'a', b'f', 0xC3, 0xA9]), Err(Error::InvalidEncoding { offset: 4 }));
+        assert_eq!(decode_str(&[0x03, b'a']), Err(Error::InputTooShort));
+    }
+    
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_decode_string() {
+        let mut buf = [0u8; 16];
+        let bytes_written = encode_str("café", &mut buf).unwrap();
+        
+        let (s, bytes_read) = decode_string(&buf[..bytes_written]).unwrap();
+        assert_eq!(s, "café");
+        assert_eq!(bytes_read, bytes_written);
+        
+        assert_eq!(decode_string(&[0x01, 0xFF]), Err(Error::InvalidEncoding { offset: 1 }));
+    }
+}
@@ -0,0 +1,201 @@
+use crate::error::Error;
+
+/// Largest encoded group: the control byte plus four 4-byte values
+pub const MAX_GROUP_LEN: usize = 17;
+
+/// Encodes four u32 values as one group varint
+///
+/// This is the layout from Google's group varint: the control byte holds four
+/// 2-bit fields, `length - 1` of each value with the first value in the highest
+/// bits, followed by each value's little-endian bytes with leading zero bytes
+/// dropped.
+///
+/// Returns the number of bytes written
+///
+/// # Errors
+/// Returns `Error::BufferTooSmall` if the buffer is too small
+pub fn encode_group_u32(values: &[u32; 4], buf: &mut [u8]) -> Result<usize, Error> {
+    let needed_size = 1 + values.iter().map(|&value| byte_len(value)).sum::<usize>();
+    
+    if buf.len() < needed_size {
+        return Err(Error::buffer_too_small(needed_size, buf.len()));
+    }
+    
+    let mut control = 0u8;
+    let mut pos = 1;
+    for (i, &value) in values.iter().enumerate() {
+        let len = byte_len(value);
+        control |= ((len - 1) as u8) << (6 - i * 2);
+        buf[pos..pos + len].copy_from_slice(&value.to_le_bytes()[..len]);
+        pos += len;
+    }
+    buf[0] = control;
+    
+    Ok(needed_size)
+}
+
+/// Decodes four u32 values from one group varint
+///
+/// Returns the decoded values and the number of bytes read
+///
+/// # Errors
+/// * Returns `Error::InputTooShort` if the input buffer is empty
+/// * Returns `Error::UnexpectedEof` if the input ends inside the group
+pub fn decode_group_u32(buf: &[u8]) -> Result<([u32; 4], usize), Error> {
+    let control = match buf.first() {
+        Some(&control) => control,
+        None => return Err(Error::input_too_short()),
+    };
+    
+    let mut lens = [0usize; 4];
+    for (i, len) in lens.iter_mut().enumerate() {
+        *len = ((control >> (6 - i * 2)) & 0b11) as usize + 1;
+    }
+    
+    let needed_size = 1 + lens.iter().sum::<usize>();
+    if buf.len() < needed_size {
+        return Err(Error::unexpected_eof());
+    }
+    
+    let mut values = [0u32; 4];
+    let mut pos = 1;
+    for (value, &len) in values.iter_mut().zip(lens.iter()) {
+        let mut bytes = [0u8; 4];
+        bytes[..len].copy_from_slice(&buf[pos..pos + len]);
+        *value = u32::from_le_bytes(bytes);
+        pos += len;
+    }
+    
+    Ok((values, needed_size))
+}
+
+/// Encodes any number of u32 values as consecutive group varints
+///
+/// A final partial group is padded with zeros, so the decoder needs the value count.
+///
+/// Returns the number of bytes written
+///
+/// # Errors
+/// Returns `Error::BufferTooSmall` if the buffer is too small
+pub fn encode_group_u32_batch(values: &[u32], buf: &mut [u8]) -> Result<usize, Error> {
+    let mut pos = 0;
+    
+    for chunk in values.chunks(4) {
+        let mut group = [0u32; 4];
+        group[..chunk.len()].copy_from_slice(chunk);
+        
+        match encode_group_u32(&group, &mut buf[pos..]) {
+            Ok(bytes_written) => pos += bytes_written,
+            Err(Error::BufferTooSmall { needed, .. }) => {
+                return Err(Error::buffer_too_small(pos + needed, buf.len()));
+            }
+            Err(e) => return Err(e),
+        }
+    }
+    
+    Ok(pos)
+}
+
+/// Decodes `values.len()` u32 values from consecutive group varints, dropping padding
+///
+/// Returns the number of bytes read
+///
+/// # Errors
+/// * Returns `Error::InputTooShort` if the input ends before a group starts
+/// * Returns `Error::UnexpectedEof` if the input ends inside a group
+pub fn decode_group_u32_batch(buf: &[u8], values: &mut [u32]) -> Result<usize, Error> {
+    let mut pos = 0;
+    
+    for chunk in values.chunks_mut(4) {
+        let (group, bytes_read) = decode_group_u32(&buf[pos..])?;
+        chunk.copy_from_slice(&group[..chunk.len()]);
+        pos += bytes_read;
+    }
+    
+    Ok(pos)
+}
+
+// Number of bytes needed to store the value, at least 1
+#[inline]
+fn byte_len(value: u32) -> usize {
+    if value == 0 {
+        1
+    } else {
+        4 - value.leading_zeros() as usize / 8
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_known_layout() {
+        let mut buf = [0u8; MAX_GROUP_LEN];
+        
+        // The example group from the original group varint description
+        let values = [1, 15, 511, 131071];
+        let bytes_written = encode_group_u32(&values, &mut buf).unwrap();
+        assert_eq!(
+            &buf[..bytes_written],
+            &[0b00_00_01_10, 0x01, 0x0F, 0xFF, 0x01, 0xFF, 0xFF, 0x01]
+        );
+        assert_eq!(decode_group_u32(&buf[..bytes_written]), Ok((values, 8)));
+        
+        let values = [1, 256, 65536, 16777216];
+        
+        let bytes_written = encode_group_u32(&values, &mut buf).unwrap();
+        assert_eq!(bytes_written, 11);
+        assert_eq!(
+            &buf[..bytes_written],
+            &[0b00_01_10_11, 0x01, 0x00, 0x01, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x01]
+        );
+        assert_eq!(decode_group_u32(&buf[..bytes_written]), Ok((values, 11)));
+        
+        let values = [0, u32::MAX, 255, 0x0100_0000 - 1];
+        let bytes_written = encode_group_u32(&values, &mut buf).unwrap();
+        assert_eq!(bytes_written, 1 + 1 + 4 + 1 + 3);
+        assert_eq!(decode_group_u32(&buf), Ok((values, bytes_written)));
+        
+        let bytes_written = encode_group_u32(&[u32::MAX; 4], &mut buf).unwrap();
+        assert_eq!(bytes_written, MAX_GROUP_LEN);
+    }
+    
+    #[test]
+    fn test_batch_with_padding() {
+        let values = [1u32, 2, 3, 4, 300, 70000];
+        let mut buf = [0u8; 2 * MAX_GROUP_LEN];
+        
+        // Two groups, the second padded with two zeros
+        let bytes_written = encode_group_u32_batch(&values, &mut buf).unwrap();
+        assert_eq!(bytes_written, 5 + 1 + 2 + 3 + 1 + 1);
+        
+        let mut decoded = [0u32; 6];
+        assert_eq!(decode_group_u32_batch(&buf[..bytes_written], &mut decoded), Ok(bytes_written));
+        assert_eq!(decoded, values);
+        
+        assert_eq!(encode_group_u32_batch(&[], &mut buf), Ok(0));
+    }
+    
+    #[test]
+    fn test_errors() {
+        assert_eq!(decode_group_u32(&[]), Err(Error::InputTooShort));
+        // Control byte announces four 4-byte values
+        assert_eq!(decode_group_u32(&[0xFF, 1, 2, 3]), Err(Error::UnexpectedEof));
+        
+        let mut small = [0u8; 4];
+        assert_eq!(
+            encode_group_u32(&[1, 2, 3, 4], &mut small),
+            Err(Error::BufferTooSmall { needed: 5, actual: 4 })
+        );
+        
+        let mut buf = [0u8; 8];
+        assert_eq!(
+            encode_group_u32_batch(&[1, 2, 3, 4, 5], &mut buf),
+            Err(Error::BufferTooSmall { needed: 10, actual: 8 })
+        );
+        
+        let mut decoded = [0u32; 8];
+        assert_eq!(decode_group_u32_batch(&[0x00, 1, 2, 3, 4], &mut decoded), Err(Error::InputTooShort));
+    }
+}
@@ -0,0 +1,91 @@
+use heapless::Vec;
+use crate::error::Error;
+use crate::traits::VarInt;
+use crate::encoding::{MAX_VARINT_LEN, encode, varint_size};
+
+/// Appends a VarInt value to a `heapless::Vec`
+///
+/// Returns the number of bytes written. Nothing is pushed if the value does not fit.
+///
+/// # Errors
+/// Returns `Error::BufferTooSmall` if the remaining capacity cannot hold the encoding
+pub fn encode_to_heapless<T: VarInt, const N: usize>(value: T, out: &mut Vec<u8, N>) -> Result<usize, Error> {
+    let mut buf = [0u8; MAX_VARINT_LEN];
+    let bytes_written = encode(value, &mut buf)?;
+    
+    if out.extend_from_slice(&buf[..bytes_written]).is_err() {
+        return Err(Error::buffer_too_small(bytes_written, N - out.len()));
+    }
+    
+    Ok(bytes_written)
+}
+
+/// Appends a batch of VarInt values to a `heapless::Vec`
+///
+/// Returns the total number of bytes written. Nothing is pushed if the batch does not fit.
+///
+/// # Errors
+/// Returns `Error::BufferTooSmall` if the remaining capacity cannot hold the whole batch
+pub fn encode_batch_to_heapless<T: VarInt, const N: usize>(values: &[T], out: &mut Vec<u8, N>) -> Result<usize, Error> {
+    let total_size: usize = values.iter().map(|&value| varint_size(value)).sum();
+    
+    if N - out.len() < total_size {
+        return Err(Error::buffer_too_small(total_size, N - out.len()));
+    }
+    
+    for &value in values {
+        encode_to_heapless(value, out)?;
+    }
+    
+    Ok(total_size)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::batch::decode_batch;
+    
+    #[test]
+    fn test_fill_until_capacity() {
+        let mut out: Vec<u8, 4> = Vec::new();
+        assert_eq!(encode_to_heapless(300u32, &mut out), Ok(2));
+        assert_eq!(encode_to_heapless(1u32, &mut out), Ok(1));
+        assert_eq!(&out[..], &[0xAC, 0x02, 0x01]);
+        
+        // 16384 needs 3 bytes but only one is left
+        assert_eq!(
+            encode_to_heapless(16384u32, &mut out),
+            Err(Error::BufferTooSmall { needed: 3, actual: 1 })
+        );
+        assert_eq!(out.len(), 3);
+        
+        assert_eq!(encode_to_heapless(127u32, &mut out), Ok(1));
+        assert_eq!(encode_to_heapless(0u32, &mut out), Err(Error::BufferTooSmall { needed: 1, actual: 0 }));
+        
+        // A value requiring a fifth byte never fits
+        let mut empty: Vec<u8, 4> = Vec::new();
+        assert_eq!(
+            encode_to_heapless(u32::MAX, &mut empty),
+            Err(Error::BufferTooSmall { needed: 5, actual: 4 })
+        );
+        assert!(empty.is_empty());
+    }
+    
+    #[test]
+    fn test_batch() {
+        let values = [1u64, 128, 16384];
+        let mut out: Vec<u8, 8> = Vec::new();
+        assert_eq!(encode_batch_to_heapless(&values, &mut out), Ok(6));
+        
+        let mut decoded = [0u64; 3];
+        decode_batch(&out, &mut decoded).unwrap();
+        assert_eq!(decoded, values);
+        
+        // The batch is rejected as a whole
+        assert_eq!(
+            encode_batch_to_heapless(&values, &mut out),
+            Err(Error::BufferTooSmall { needed: 6, actual: 2 })
+        );
+        assert_eq!(out.len(), 6);
+    }
+}
@@ -0,0 +1,131 @@
+use std::io::{self, Read, Write};
+use crate::error::Error;
+use crate::traits::{VarInt, VarIntOps};
+use crate::zigzag::ZigZag;
+use crate::encoding::{encode_array, push_byte};
+
+/// Extension trait adding varint writing to any `std::io::Write`
+pub trait WriteVarint: Write {
+    /// Writes a VarInt value to the sink
+    ///
+    /// Returns the number of bytes written
+    fn write_varint<T: VarInt>(&mut self, value: T) -> io::Result<usize> {
+        let (buf, len) = encode_array(value);
+        self.write_all(&buf[..len])?;
+        Ok(len)
+    }
+    
+    /// Writes a signed value to the sink using zigzag encoding
+    ///
+    /// Returns the number of bytes written
+    fn write_varint_zigzag<T: ZigZag>(&mut self, value: T) -> io::Result<usize> {
+        self.write_varint(value.zigzag_encode())
+    }
+}
+
+impl<W: Write + ?Sized> WriteVarint for W {}
+
+/// Extension trait adding varint reading to any `std::io::Read`
+pub trait ReadVarint: Read {
+    /// Reads a VarInt value from the source, one byte at a time
+    ///
+    /// # Errors
+    /// * Returns `io::ErrorKind::UnexpectedEof` if the source ends mid-value
+    /// * Returns `io::ErrorKind::InvalidData` if the value overflows or cannot be represented in `T`
+    fn read_varint<T: VarInt>(&mut self) -> io::Result<T> {
+        let mut result = T::Unsigned::from_byte(0, 0);
+        let mut shift = 0;
+        
+        loop {
+            let mut byte = [0u8; 1];
+            self.read_exact(&mut byte)?;
+            
+            if let Some(value) = push_byte(&mut result, byte[0], &mut shift).map_err(to_io_error)? {
+                return Ok(value);
+            }
+        }
+    }
+    
+    /// Reads a signed value that was encoded using zigzag encoding
+    fn read_varint_zigzag<T: ZigZag>(&mut self) -> io::Result<T> {
+        Ok(T::zigzag_decode(self.read_varint()?))
+    }
+}
+
+impl<R: Read + ?Sized> ReadVarint for R {}
+
+// Map a decoding error onto the closest io::ErrorKind
+pub(crate) fn to_io_error(err: Error) -> io::Error {
+    match err {
+        Error::InputTooShort | Error::UnexpectedEof => io::Error::from(io::ErrorKind::UnexpectedEof),
+        Error::Overflow { .. } => io::Error::new(io::ErrorKind::InvalidData, "varint overflow"),
+        _ => io::Error::new(io::ErrorKind::InvalidData, "invalid varint encoding"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::vec::Vec;
+    use crate::encoding::{MAX_VARINT_LEN, decode};
+    use crate::zigzag::decode_zigzag;
+    
+    #[test]
+    fn test_write_varint() {
+        let mut out = Vec::new();
+        assert_eq!(out.write_varint(300u32).unwrap(), 2);
+        assert_eq!(out.write_varint(u128::MAX).unwrap(), MAX_VARINT_LEN);
+        
+        let (first, bytes_read) = decode::<u32>(&out).unwrap();
+        assert_eq!(first, 300);
+        let (second, _) = decode::<u128>(&out[bytes_read..]).unwrap();
+        assert_eq!(second, u128::MAX);
+    }
+    
+    #[test]
+    fn test_write_varint_zigzag() {
+        let mut out = Vec::new();
+        assert_eq!(out.write_varint_zigzag(-1i64).unwrap(), 1);
+        
+        let (value, _) = decode_zigzag::<i64>(&out).unwrap();
+        assert_eq!(value, -1);
+    }
+    
+    #[test]
+    fn test_read_varint() {
+        let mut out = Vec::new();
+        out.write_varint(16384u64).unwrap();
+        out.write_varint_zigzag(-300i32).unwrap();
+        
+        let mut source = &out[..];
+        assert_eq!(source.read_varint::<u64>().unwrap(), 16384);
+        assert_eq!(source.read_varint_zigzag::<i32>().unwrap(), -300);
+        assert!(source.is_empty());
+    }
+    
+    #[test]
+    fn test_read_varint_errors() {
+        // Continuation bit set but the stream ends
+        let mut truncated: &[u8] = &[0x80, 0x80];
+        let err = truncated.read_varint::<u64>().unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+        
+        // Empty stream
+        let mut empty: &[u8] = &[];
+        let err = empty.read_varint::<u32>().unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+        
+        // Too many continuation bytes for a u8
+        let mut overlong: &[u8] = &[0x80, 0x80, 0x01];
+        let err = overlong.read_varint::<u8>().unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+    
+    #[test]
+    fn test_write_error_propagates() {
+        let mut storage = [0u8; 1];
+        let mut sink: &mut [u8] = &mut storage;
+        let err = sink.write_varint(1000u64).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::WriteZero);
+    }
+}
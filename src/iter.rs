@@ -1,7 +1,10 @@
 use crate::error::Error;
 use crate::traits::{VarInt, VarIntOps};
-use crate::encoding::decode;
+use crate::encoding::{MAX_VARINT_LEN, decode};
+use crate::batch::count_varints;
+use crate::zigzag::ZigZag;
 use core::marker::PhantomData;
+use core::ops::Range;
 
 /// Iterator representing varint encoded bytes
 pub struct VarIntBytesIter<T: VarInt = u64> 
@@ -38,6 +41,20 @@ where T::Unsigned: VarIntOps {
     pub fn size(&self) -> usize {
         self.size
     }
+    
+    /// Drives the iterator to completion into a stack array
+    ///
+    /// Returns the array and the number of bytes used. Bytes already taken
+    /// from the iterator are not included.
+    pub fn to_array(self) -> ([u8; MAX_VARINT_LEN], usize) {
+        let mut buf = [0u8; MAX_VARINT_LEN];
+        let mut len = 0;
+        for byte in self {
+            buf[len] = byte;
+            len += 1;
+        }
+        (buf, len)
+    }
 }
 
 impl<T: VarInt> Iterator for VarIntBytesIter<T> 
@@ -72,6 +89,53 @@ where T::Unsigned: VarIntOps {
     }
 }
 
+/// Iterator yielding the concatenated varint encoding of a sequence of values
+pub struct EncodeIter<I, T: VarInt = u64> {
+    values: I,
+    current: Option<VarIntBytesIter<T>>,
+}
+
+impl<I: Iterator<Item = T>, T: VarInt> EncodeIter<I, T> {
+    /// Creates a new encoder over the source values
+    pub fn new(values: I) -> Self {
+        EncodeIter {
+            values,
+            current: None,
+        }
+    }
+}
+
+impl<I: Iterator<Item = T>, T: VarInt> Iterator for EncodeIter<I, T> {
+    type Item = u8;
+    
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(byte) = self.current.as_mut().and_then(Iterator::next) {
+                return Some(byte);
+            }
+            
+            // Current value exhausted, move on to the next source value
+            self.current = Some(VarIntBytesIter::new(self.values.next()?));
+        }
+    }
+    
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (current_lower, current_upper) = match &self.current {
+            Some(bytes) => bytes.size_hint(),
+            None => (0, Some(0)),
+        };
+        let (values_lower, values_upper) = self.values.size_hint();
+        
+        // Every value takes between 1 and MAX_ENCODED_LEN bytes
+        let upper = values_upper
+            .and_then(|n| n.checked_mul(T::MAX_ENCODED_LEN))
+            .zip(current_upper)
+            .and_then(|(a, b)| a.checked_add(b));
+        
+        (current_lower.saturating_add(values_lower), upper)
+    }
+}
+
 /// Iterator for decoding varint values from a byte buffer
 pub struct VarIntValuesIter<'a, T: VarInt = u64> {
     buf: &'a [u8],
@@ -100,6 +164,21 @@ impl<'a, T: VarInt> VarIntValuesIter<'a, T> {
     pub fn remaining(&self) -> &'a [u8] {
         &self.buf[self.pos..]
     }
+    
+    /// Counts the values left to yield by scanning for varint terminators
+    ///
+    /// Nothing is decoded and the position does not move, unlike `Iterator::count`.
+    /// Values that would fail to decode, such as ones too wide for `T`, are still counted.
+    ///
+    /// # Errors
+    /// Returns `Error::UnexpectedEof` if the remaining bytes end with a dangling continuation byte
+    pub fn count_remaining(&self) -> Result<usize, Error> {
+        if self.finished {
+            return Ok(0);
+        }
+        
+        count_varints(self.remaining())
+    }
 }
 
 impl<'a, T: VarInt> Iterator for VarIntValuesIter<'a, T> {
@@ -128,7 +207,41 @@ pub fn bytes_of<T: VarInt>(value: T) -> VarIntBytesIter<T> {
     VarIntBytesIter::new(value)
 }
 
+/// Helper function to lazily encode an iterator of values into a byte iterator
+pub fn encode_iter<I: Iterator<Item = T>, T: VarInt>(values: I) -> EncodeIter<I, T> {
+    EncodeIter::new(values)
+}
+
 /// Helper function to create a values decoder from a buffer
 pub fn values_from<'a, T: VarInt>(buf: &'a [u8]) -> VarIntValuesIter<'a, T> {
     VarIntValuesIter::new(buf)
-} 
\ No newline at end of file
+}
+
+/// Helper function to create a values decoder that also yields the byte range of each value
+///
+/// Each item is the value with the `start..end` offsets it occupied in `buf`,
+/// for building an index over a packed buffer. Stops after the first error.
+pub fn indexed_values_from<'a, T: VarInt + 'a>(buf: &'a [u8]) -> impl Iterator<Item = Result<(T, Range<usize>), Error>> + 'a {
+    let mut values = values_from::<T>(buf);
+    core::iter::from_fn(move || {
+        let start = values.position();
+        let item = values.next()?;
+        Some(item.map(|value| (value, start..values.position())))
+    })
+}
+
+/// Helper function to create a bytes encoder for a signed value using zigzag encoding
+///
+/// The value is zigzag-transformed up front, so `size()` reports the length of the
+/// zigzag encoding
+pub fn bytes_of_zigzag<S: ZigZag>(value: S) -> VarIntBytesIter<S::Unsigned> {
+    VarIntBytesIter::new(value.zigzag_encode())
+}
+
+/// Helper function to create a decoder of zigzag-encoded signed values from a buffer
+///
+/// Each value is decoded as `S::Unsigned` and then zigzag-decoded. Use `take(n)`
+/// to read a fixed count from a longer stream. Stops after the first error.
+pub fn zigzag_values_from<'a, S: ZigZag + 'a>(buf: &'a [u8]) -> impl Iterator<Item = Result<S, Error>> + 'a {
+    values_from::<S::Unsigned>(buf).map(|result| result.map(S::zigzag_decode))
+}
@@ -0,0 +1,203 @@
+use crate::error::Error;
+
+/// Trait for signed integers encoded as two's complement signed LEB128
+pub trait Leb128Signed: Copy {
+    /// Number of bits in the type
+    const BITS: usize;
+    
+    /// Sign-extend the value to an i128
+    fn to_i128(self) -> i128;
+    
+    /// Truncate an i128 to this type
+    fn from_i128(value: i128) -> Self;
+}
+
+// Implement Leb128Signed for signed types
+macro_rules! impl_leb128_signed {
+    ($type:ty, $bits:expr) => {
+        impl Leb128Signed for $type {
+            const BITS: usize = $bits;
+            
+            #[inline]
+            fn to_i128(self) -> i128 {
+                self as i128
+            }
+            
+            #[inline]
+            fn from_i128(value: i128) -> Self {
+                value as Self
+            }
+        }
+    };
+}
+
+impl_leb128_signed!(i8, 8);
+impl_leb128_signed!(i16, 16);
+impl_leb128_signed!(i32, 32);
+impl_leb128_signed!(i64, 64);
+impl_leb128_signed!(i128, 128);
+
+/// Calculates the number of bytes needed to encode a value as signed LEB128
+pub fn leb128_signed_size<T: Leb128Signed>(value: T) -> usize {
+    let mut val = value.to_i128();
+    let mut size = 1;
+    
+    // Stop once the remaining bits are pure sign extension of the last byte
+    while !(-64..64).contains(&val) {
+        val >>= 7;
+        size += 1;
+    }
+    
+    size
+}
+
+/// Encodes a signed integer as two's complement signed LEB128 (as used by DWARF and WebAssembly)
+///
+/// Returns the number of bytes written
+///
+/// # Parameters
+/// * `value` - The value to encode
+/// * `buf` - Output buffer
+///
+/// # Errors
+/// Returns `Error::BufferTooSmall` if the buffer is too small
+pub fn encode_leb128_signed<T: Leb128Signed>(value: T, buf: &mut [u8]) -> Result<usize, Error> {
+    let needed_size = leb128_signed_size(value);
+    
+    if buf.len() < needed_size {
+        return Err(Error::buffer_too_small(needed_size, buf.len()));
+    }
+    
+    let mut val = value.to_i128();
+    for byte in buf.iter_mut().take(needed_size - 1) {
+        *byte = (val as u8) | 0x80;
+        val >>= 7;
+    }
+    buf[needed_size - 1] = (val as u8) & 0x7F;
+    
+    Ok(needed_size)
+}
+
+/// Decodes a signed integer from two's complement signed LEB128, sign-extending the result
+///
+/// Returns the decoded value and the number of bytes read
+///
+/// # Parameters
+/// * `buf` - Input buffer containing signed LEB128 encoding
+///
+/// # Errors
+/// * Returns `Error::InputTooShort` if the input buffer is empty
+/// * Returns `Error::UnexpectedEof` if the input ends after a byte with the continuation bit set
+/// * Returns `Error::Overflow` if the encoded value does not fit in `T`
+pub fn decode_leb128_signed<T: Leb128Signed>(buf: &[u8]) -> Result<(T, usize), Error> {
+    let max_len = T::BITS.div_ceil(7);
+    let mut result: i128 = 0;
+    let mut shift = 0;
+    let mut i = 0;
+    
+    loop {
+        if i >= buf.len() {
+            return Err(if i == 0 { Error::input_too_short() } else { Error::unexpected_eof() });
+        }
+        
+        let byte = buf[i];
+        i += 1;
+        
+        // The group holding the sign bit of T: everything above it must be sign extension
+        if shift + 7 >= T::BITS {
+            let upper = (byte & 0x7F) >> (T::BITS - shift - 1);
+            if byte & 0x80 != 0 || (upper != 0 && upper != 0x7F >> (T::BITS - shift - 1)) {
+                return Err(Error::overflow(i));
+            }
+        }
+        
+        result |= ((byte & 0x7F) as i128) << shift;
+        shift += 7;
+        
+        // Check if done
+        if byte & 0x80 == 0 {
+            // Sign-extend from the last byte's sign bit
+            if shift < 128 && byte & 0x40 != 0 {
+                result |= -1i128 << shift;
+            }
+            break;
+        }
+        
+        // Prevent too large encoding
+        if i >= max_len {
+            return Err(Error::overflow(i));
+        }
+    }
+    
+    Ok((T::from_i128(result), i))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_known_encodings() {
+        let cases: [(i64, &[u8]); 8] = [
+            (0, &[0x00]),
+            (-1, &[0x7F]),
+            (63, &[0x3F]),
+            (64, &[0xC0, 0x00]),
+            (-64, &[0x40]),
+            (-65, &[0xBF, 0x7F]),
+            (-123456, &[0xC0, 0xBB, 0x78]),
+            (i64::MIN, &[0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x7F]),
+        ];
+        
+        for (value, expected) in cases {
+            let mut buf = [0u8; 10];
+            let bytes_written = encode_leb128_signed(value, &mut buf).unwrap();
+            assert_eq!(&buf[..bytes_written], expected, "Encoding mismatch for {}", value);
+            assert_eq!(leb128_signed_size(value), expected.len());
+            assert_eq!(decode_leb128_signed::<i64>(expected), Ok((value, expected.len())));
+        }
+        
+        // WebAssembly i32.const immediates
+        let mut buf = [0u8; 5];
+        let bytes_written = encode_leb128_signed(i32::MIN, &mut buf).unwrap();
+        assert_eq!(&buf[..bytes_written], &[0x80, 0x80, 0x80, 0x80, 0x78]);
+        assert_eq!(decode_leb128_signed::<i32>(&buf), Ok((i32::MIN, 5)));
+        assert_eq!(decode_leb128_signed::<i32>(&[0xC0, 0xBB, 0x78]), Ok((-123456, 3)));
+    }
+    
+    #[test]
+    fn test_round_trip_extremes() {
+        macro_rules! check_round_trip {
+            ($($type:ty),*) => {
+                $(
+                    for value in [<$type>::MIN, <$type>::MAX, 0, -1, 1] {
+                        let mut buf = [0u8; 19];
+                        let bytes_written = encode_leb128_signed(value, &mut buf).unwrap();
+                        assert!(bytes_written <= (<$type>::BITS as usize).div_ceil(7));
+                        assert_eq!(decode_leb128_signed::<$type>(&buf), Ok((value, bytes_written)));
+                    }
+                )*
+            };
+        }
+        check_round_trip!(i8, i16, i32, i64, i128);
+    }
+    
+    #[test]
+    fn test_errors() {
+        // 128 does not fit in an i8
+        assert_eq!(decode_leb128_signed::<i8>(&[0x80, 0x01]), Err(Error::Overflow { bytes_read: 2 }));
+        // -129 does not fit in an i8
+        assert_eq!(decode_leb128_signed::<i8>(&[0xFF, 0x7E]), Err(Error::Overflow { bytes_read: 2 }));
+        // Too many bytes for an i32
+        assert_eq!(decode_leb128_signed::<i32>(&[0x80, 0x80, 0x80, 0x80, 0x80, 0x00]), Err(Error::Overflow { bytes_read: 5 }));
+        
+        assert_eq!(decode_leb128_signed::<i32>(&[0x80]), Err(Error::UnexpectedEof));
+        assert_eq!(decode_leb128_signed::<i32>(&[]), Err(Error::InputTooShort));
+        
+        let mut small = [0u8; 2];
+        assert_eq!(
+            encode_leb128_signed(-123456i32, &mut small),
+            Err(Error::BufferTooSmall { needed: 3, actual: 2 })
+        );
+    }
+}
@@ -11,12 +11,28 @@
 //!
 //! ## Features
 //!
-//! * **Generic Integer Support**: Works with all integer types (u8-u128, i8-i128)
+//! * **Generic Integer Support**: Works with all integer types (u8-u128, i8-i128, usize, isize) and their `NonZero` counterparts
 //! * **Batch Processing API**: Efficiently handle multiple values with state management
 //! * **Iterator-based API**: Memory-efficient processing using iterator methods
+//! * **Cursor**: `VarIntCursor` reads and writes varints of any type at one shared position
+//! * **Streaming Decoder**: `StreamDecoder` for varints split across arbitrary chunks
 //! * **Basic Encoding Functions**: Low-level functions for direct use
 //! * **ZigZag Support**: Efficient encoding of signed integers
+//! * **Group Varint**: Google's group-varint format for fast decoding of u32 streams
+//! * **SQLite Varints**: The big-endian 1-9 byte format used in SQLite database files
+//! * **MIDI VLQ**: Big-endian variable-length quantities as used in MIDI files
+//! * **Signed LEB128**: Two's complement signed LEB128 as used by DWARF and WebAssembly
+//! * **Tuples**: `encode_tuple`/`decode_tuple` for tuples of up to six integers
 //! * **Unified Value Type**: VarintValue enum for type-aware encoding/decoding
+//! * **Borrowed Values**: `VarintValueRef` mixes integers and byte strings in one tagged stream without allocating
+//! * **Vec Helpers**: `encode_to_vec`, `encode_batch_to_vec` and `decode_all` (requires the `alloc` feature)
+//! * **Nested Values**: `DynValue` for self-describing nested data (requires the `alloc` feature)
+//! * **I/O Integration**: `WriteVarint`/`ReadVarint` extension traits for `std::io` (requires the `std` feature)
+//! * **bytes Integration**: `put_varint`/`get_varint` for `BufMut`/`Buf` (requires the `bytes` feature)
+//! * **heapless Integration**: `encode_to_heapless`/`encode_batch_to_heapless` for `heapless::Vec` (requires the `heapless` feature)
+//! * **proptest Strategies**: `any_varint_value`/`any_varint_bytes` for property testing (requires the `proptest` feature)
+//! * **Async I/O**: `async_io::read_varint`/`async_io::write_varint` over tokio's `AsyncRead`/`AsyncWrite` (requires the `tokio` feature)
+//! * **Hex Dump**: `write_hex` prints encoded bytes to any `core::fmt::Write` sink for debugging
 //! * **No-std Compatible**: Works in embedded environments
 //!
 //! ## Usage Examples
@@ -120,6 +136,12 @@
 // Import zigzag-rs for ZigZag encoding/decoding
 extern crate zigzag_rs;
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(feature = "std")]
+extern crate std;
+
 // Define modules
 mod error;
 mod traits;
@@ -128,15 +150,63 @@ mod batch;
 mod iter;
 mod zigzag;
 mod value;
+mod value_ref;
+mod leb128;
+mod primitives;
+mod framing;
+mod stream;
+mod group_varint;
+mod sqlite;
+mod vlq;
+mod tuple;
+mod debug;
+mod cursor;
+#[cfg(feature = "alloc")]
+mod dyn_value;
+#[cfg(feature = "std")]
+mod io;
+#[cfg(feature = "bytes")]
+mod bytes_integration;
+#[cfg(feature = "heapless")]
+mod heapless_integration;
+#[cfg(feature = "tokio")]
+pub mod async_io;
+#[cfg(feature = "proptest")]
+mod proptest_integration;
 #[cfg(test)]
 mod tests;
 
 // Re-export all public items
 pub use error::Error;
 pub use traits::VarInt;
-pub use encoding::{encode, decode, varint_size};
-pub use zigzag::{ZigZag, encode_zigzag, decode_zigzag};
-pub use batch::{VarIntEncoder, VarIntDecoder, encode_batch, decode_batch};
-pub use iter::{VarIntBytesIter, VarIntValuesIter, bytes_of, values_from};
-pub use value::VarintValue;
+pub use encoding::{MAX_VARINT_LEN, Endianness, encode, encode_at, encode_array, encode_u64_const, encode_max_len, decode, decode_narrowing, decode_saturating, decode_split, decode_after_sentinel, decode_from_iter, decode_canonical, decode_limited, encode_with_endian, decode_with_endian, varint_size};
+pub use zigzag::{ZigZag, zigzag_encode_value, zigzag_decode_value, zigzag_size, encode_zigzag, decode_zigzag, decode_zigzag_split};
+pub use batch::{VarIntEncoder, VarIntDecoder, VarIntDecoderIter, VarIntSink, encode_batch, decode_batch, decode_batch_trusted, encode_zigzag_batch, decode_zigzag_batch, zigzag_batch_size, decode_packed, decode_until, read_at, validate_stream, fold_values, sum_values, decode_recoverable, count_varints, size_histogram};
+#[cfg(feature = "alloc")]
+pub use batch::{encode_to_vec, encode_batch_to_vec, decode_all};
+pub use iter::{VarIntBytesIter, VarIntValuesIter, EncodeIter, bytes_of, encode_iter, values_from, indexed_values_from, bytes_of_zigzag, zigzag_values_from};
+pub use value::{VarintValue, VarintTypeTag, type_id_for, tag_for_type_id, VarintValueWriter, VarintValueReader, varint_values_from, decode_with_schema, batch_serialized_size, to_bytes_batch, from_bytes_batch};
+pub use value_ref::VarintValueRef;
+pub use framing::{encode_bytes, decode_bytes, encode_str, decode_str, prepend_length};
+#[cfg(feature = "alloc")]
+pub use framing::decode_string;
+pub use stream::StreamDecoder;
+pub use group_varint::{MAX_GROUP_LEN, encode_group_u32, decode_group_u32, encode_group_u32_batch, decode_group_u32_batch};
+pub use primitives::{encode_bool, decode_bool, encode_char, decode_char, encode_f32, decode_f32, encode_f64, decode_f64};
+pub use sqlite::{encode_sqlite, decode_sqlite, sqlite_size};
+pub use vlq::{VLQ_MAX, encode_vlq, decode_vlq, vlq_size};
+pub use tuple::{TupleField, VarIntTuple, encode_tuple, decode_tuple};
+pub use debug::write_hex;
+pub use cursor::VarIntCursor;
+pub use leb128::{Leb128Signed, encode_leb128_signed, decode_leb128_signed, leb128_signed_size};
+#[cfg(feature = "alloc")]
+pub use dyn_value::{DynValue, DEFAULT_MAX_DEPTH};
+#[cfg(feature = "std")]
+pub use io::{WriteVarint, ReadVarint};
+#[cfg(feature = "bytes")]
+pub use bytes_integration::{put_varint, get_varint};
+#[cfg(feature = "heapless")]
+pub use heapless_integration::{encode_to_heapless, encode_batch_to_heapless};
+#[cfg(feature = "proptest")]
+pub use proptest_integration::{any_varint_value, any_varint_bytes};
 // varint! macro is re-exported via #[macro_export]
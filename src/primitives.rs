@@ -0,0 +1,177 @@
+use crate::error::Error;
+use crate::encoding::{encode, decode};
+
+/// Encodes a bool as a single `0x00` or `0x01` byte
+///
+/// Returns the number of bytes written
+///
+/// # Errors
+/// Returns `Error::BufferTooSmall` if the buffer is empty
+pub fn encode_bool(value: bool, buf: &mut [u8]) -> Result<usize, Error> {
+    encode(value as u8, buf)
+}
+
+/// Decodes a bool from a single `0x00` or `0x01` byte
+///
+/// Returns the decoded value and the number of bytes read
+///
+/// # Errors
+/// * Returns `Error::InputTooShort` if the buffer is empty
+/// * Returns `Error::InvalidEncoding` if the byte is neither `0x00` nor `0x01`
+pub fn decode_bool(buf: &[u8]) -> Result<(bool, usize), Error> {
+    match buf.first() {
+        Some(0x00) => Ok((false, 1)),
+        Some(0x01) => Ok((true, 1)),
+        Some(_) => Err(Error::invalid_encoding(0)),
+        None => Err(Error::input_too_short()),
+    }
+}
+
+/// Encodes a char as the varint of its `u32` scalar value
+///
+/// Returns the number of bytes written
+///
+/// # Errors
+/// Returns `Error::BufferTooSmall` if the buffer is too small
+pub fn encode_char(value: char, buf: &mut [u8]) -> Result<usize, Error> {
+    encode(value as u32, buf)
+}
+
+/// Decodes a char from the varint of its `u32` scalar value
+///
+/// Returns the decoded value and the number of bytes read
+///
+/// # Errors
+/// * Returns `Error::InputTooShort` or `Error::UnexpectedEof` if the input buffer is insufficient
+/// * Returns `Error::InvalidEncoding` if the value is not a Unicode scalar value
+/// * Returns `Error::Overflow` if the value does not fit in a `u32`
+pub fn decode_char(buf: &[u8]) -> Result<(char, usize), Error> {
+    let (scalar, bytes_read) = decode::<u32>(buf)?;
+    
+    match char::from_u32(scalar) {
+        Some(value) => Ok((value, bytes_read)),
+        None => Err(Error::invalid_encoding(0)),
+    }
+}
+
+/// Encodes an f32 as the varint of its IEEE 754 bit pattern
+///
+/// This gives no size savings for typical floats (most need the full 5 bytes),
+/// but keeps floats in the same varint stream format. NaN payloads, infinities
+/// and signed zeros are preserved exactly.
+///
+/// Returns the number of bytes written
+///
+/// # Errors
+/// Returns `Error::BufferTooSmall` if the buffer is too small
+pub fn encode_f32(value: f32, buf: &mut [u8]) -> Result<usize, Error> {
+    encode(value.to_bits(), buf)
+}
+
+/// Decodes an f32 from the varint of its IEEE 754 bit pattern
+///
+/// Returns the decoded value and the number of bytes read
+///
+/// # Errors
+/// * Returns `Error::InputTooShort` or `Error::UnexpectedEof` if the input buffer is insufficient
+/// * Returns `Error::Overflow` if the value does not fit in a `u32`
+pub fn decode_f32(buf: &[u8]) -> Result<(f32, usize), Error> {
+    let (bits, bytes_read) = decode::<u32>(buf)?;
+    Ok((f32::from_bits(bits), bytes_read))
+}
+
+/// Encodes an f64 as the varint of its IEEE 754 bit pattern
+///
+/// Like `encode_f32`, this is about a uniform stream format rather than
+/// compression; typical values need 9 or 10 bytes.
+///
+/// Returns the number of bytes written
+///
+/// # Errors
+/// Returns `Error::BufferTooSmall` if the buffer is too small
+pub fn encode_f64(value: f64, buf: &mut [u8]) -> Result<usize, Error> {
+    encode(value.to_bits(), buf)
+}
+
+/// Decodes an f64 from the varint of its IEEE 754 bit pattern
+///
+/// Returns the decoded value and the number of bytes read
+///
+/// # Errors
+/// * Returns `Error::InputTooShort` or `Error::UnexpectedEof` if the input buffer is insufficient
+/// * Returns `Error::Overflow` if the value does not fit in a `u64`
+pub fn decode_f64(buf: &[u8]) -> Result<(f64, usize), Error> {
+    let (bits, bytes_read) = decode::<u64>(buf)?;
+    Ok((f64::from_bits(bits), bytes_read))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_bool_round_trip() {
+        let mut buf = [0u8; 1];
+        
+        assert_eq!(encode_bool(true, &mut buf), Ok(1));
+        assert_eq!(buf, [0x01]);
+        assert_eq!(decode_bool(&buf), Ok((true, 1)));
+        
+        assert_eq!(encode_bool(false, &mut buf), Ok(1));
+        assert_eq!(buf, [0x00]);
+        assert_eq!(decode_bool(&buf), Ok((false, 1)));
+        
+        assert_eq!(decode_bool(&[0x02]), Err(Error::InvalidEncoding { offset: 0 }));
+        assert_eq!(decode_bool(&[]), Err(Error::InputTooShort));
+        assert!(encode_bool(true, &mut []).is_err());
+    }
+    
+    #[test]
+    fn test_char_round_trip() {
+        let mut buf = [0u8; 5];
+        
+        for value in ['A', '\u{0}', 'é', '🦀', char::MAX] {
+            let bytes_written = encode_char(value, &mut buf).unwrap();
+            assert_eq!(decode_char(&buf[..bytes_written]), Ok((value, bytes_written)));
+        }
+        
+        assert_eq!(encode_char('A', &mut buf), Ok(1));
+        assert_eq!(buf[0], 0x41);
+    }
+    
+    #[test]
+    fn test_char_rejects_invalid_scalars() {
+        let mut buf = [0u8; 5];
+        
+        // Surrogate
+        let bytes_written = encode(0xD800u32, &mut buf).unwrap();
+        assert_eq!(decode_char(&buf[..bytes_written]), Err(Error::InvalidEncoding { offset: 0 }));
+        
+        // Beyond the Unicode range
+        let bytes_written = encode(0x110000u32, &mut buf).unwrap();
+        assert_eq!(decode_char(&buf[..bytes_written]), Err(Error::InvalidEncoding { offset: 0 }));
+    }
+    
+    #[test]
+    fn test_float_round_trip() {
+        let mut buf = [0u8; 10];
+        
+        for value in [0.0f32, -0.0, 1.5, f32::INFINITY, f32::NEG_INFINITY, f32::NAN] {
+            let bytes_written = encode_f32(value, &mut buf).unwrap();
+            let (decoded, bytes_read) = decode_f32(&buf[..bytes_written]).unwrap();
+            assert_eq!(decoded.to_bits(), value.to_bits());
+            assert_eq!(bytes_read, bytes_written);
+        }
+        
+        for value in [0.0f64, -0.0, -2.25, f64::INFINITY, f64::NAN, f64::from_bits(0x7FF0_0000_0000_0001)] {
+            let bytes_written = encode_f64(value, &mut buf).unwrap();
+            let (decoded, bytes_read) = decode_f64(&buf[..bytes_written]).unwrap();
+            assert_eq!(decoded.to_bits(), value.to_bits());
+            assert_eq!(bytes_read, bytes_written);
+        }
+        
+        // Positive zero is the only float that compresses to a single byte
+        assert_eq!(encode_f64(0.0, &mut buf), Ok(1));
+        assert_eq!(encode_f64(-0.0, &mut buf), Ok(10));
+    }
+}
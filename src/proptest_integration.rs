@@ -0,0 +1,83 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use proptest::prelude::*;
+use crate::value::VarintValue;
+
+/// Strategy generating every `VarintValue` variant across its full range
+pub fn any_varint_value() -> impl Strategy<Value = VarintValue> {
+    prop_oneof![
+        any::<u8>().prop_map(VarintValue::U8),
+        any::<u16>().prop_map(VarintValue::U16),
+        any::<u32>().prop_map(VarintValue::U32),
+        any::<u64>().prop_map(VarintValue::U64),
+        any::<u128>().prop_map(VarintValue::U128),
+        any::<i8>().prop_map(VarintValue::I8),
+        any::<i16>().prop_map(VarintValue::I16),
+        any::<i32>().prop_map(VarintValue::I32),
+        any::<i64>().prop_map(VarintValue::I64),
+        any::<i128>().prop_map(VarintValue::I128),
+    ]
+}
+
+/// Strategy generating `VarintValue::to_bytes` encodings, both valid and subtly broken
+///
+/// Besides valid encodings, it yields truncated ones, ones with a single bit
+/// flipped, and ones with an extra continuation byte, so decoders can be
+/// checked for clean errors rather than panics.
+pub fn any_varint_bytes() -> impl Strategy<Value = Vec<u8>> {
+    prop_oneof![
+        valid_bytes(),
+        // Truncated: last byte dropped
+        valid_bytes().prop_map(|mut buf| {
+            buf.pop();
+            buf
+        }),
+        // One bit flipped anywhere, including the type byte
+        (valid_bytes(), any::<prop::sample::Index>(), 0..8u8).prop_map(|(mut buf, index, bit)| {
+            let i = index.index(buf.len());
+            buf[i] ^= 1 << bit;
+            buf
+        }),
+        // Over-long: continuation set on the last byte, followed by a zero group
+        valid_bytes().prop_map(|mut buf| {
+            if let Some(last) = buf.last_mut() {
+                *last |= 0x80;
+            }
+            buf.push(0x00);
+            buf
+        }),
+    ]
+}
+
+// Valid `to_bytes` encodings of arbitrary values
+fn valid_bytes() -> impl Strategy<Value = Vec<u8>> {
+    any_varint_value().prop_map(|value| {
+        let mut buf = vec![0u8; value.serialized_size()];
+        let len = value.to_bytes(&mut buf).expect("buffer sized by serialized_size");
+        buf.truncate(len);
+        buf
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    proptest! {
+        #[test]
+        fn test_varint_value_round_trip(value in any_varint_value()) {
+            let mut buf = [0u8; 20];
+            let bytes_written = value.to_bytes(&mut buf).unwrap();
+            prop_assert_eq!(bytes_written, value.serialized_size());
+            prop_assert_eq!(VarintValue::from_bytes(&buf[..bytes_written]), Ok((value, bytes_written)));
+        }
+        
+        #[test]
+        fn test_varint_bytes_decode_cleanly(bytes in any_varint_bytes()) {
+            // Any outcome is fine as long as it is not a panic and stays in bounds
+            if let Ok((_, bytes_read)) = VarintValue::from_bytes(&bytes) {
+                prop_assert!(bytes_read <= bytes.len());
+            }
+        }
+    }
+}
@@ -0,0 +1,148 @@
+use crate::error::Error;
+
+/// Calculates the number of bytes needed to encode a value in the SQLite varint format
+pub fn sqlite_size(value: u64) -> usize {
+    // Values above 56 bits use the 9-byte form
+    if value >> 56 != 0 {
+        return 9;
+    }
+    
+    let bits_needed = 64 - value.leading_zeros() as usize;
+    bits_needed.div_ceil(7).max(1)
+}
+
+/// Encodes a u64 in the SQLite database file varint format
+///
+/// Unlike LEB128 the groups are big-endian: each of the first eight bytes carries
+/// 7 bits with the high bit meaning "more follows", and a ninth byte, if present,
+/// carries all 8 of its bits.
+///
+/// Returns the number of bytes written
+///
+/// # Parameters
+/// * `value` - The value to encode
+/// * `buf` - Output buffer
+///
+/// # Errors
+/// Returns `Error::BufferTooSmall` if the buffer is too small
+pub fn encode_sqlite(value: u64, buf: &mut [u8]) -> Result<usize, Error> {
+    let needed_size = sqlite_size(value);
+    
+    if buf.len() < needed_size {
+        return Err(Error::buffer_too_small(needed_size, buf.len()));
+    }
+    
+    let mut val = value;
+    let mut last = needed_size - 1;
+    
+    // The ninth byte holds a full 8 bits
+    if needed_size == 9 {
+        buf[8] = val as u8;
+        val >>= 8;
+        last = 7;
+        buf[last] = (val as u8 & 0x7F) | 0x80;
+    } else {
+        buf[last] = val as u8 & 0x7F;
+    }
+    val >>= 7;
+    
+    for byte in buf[..last].iter_mut().rev() {
+        *byte = (val as u8 & 0x7F) | 0x80;
+        val >>= 7;
+    }
+    
+    Ok(needed_size)
+}
+
+/// Decodes a u64 from the SQLite database file varint format
+///
+/// Returns the decoded value and the number of bytes read
+///
+/// # Parameters
+/// * `buf` - Input buffer containing SQLite varint encoding
+///
+/// # Errors
+/// * Returns `Error::InputTooShort` if the input buffer is empty
+/// * Returns `Error::UnexpectedEof` if the input ends after a byte with the continuation bit set
+pub fn decode_sqlite(buf: &[u8]) -> Result<(u64, usize), Error> {
+    let mut result = 0u64;
+    
+    for i in 0..9 {
+        let byte = match buf.get(i) {
+            Some(&byte) => byte,
+            None if i == 0 => return Err(Error::input_too_short()),
+            None => return Err(Error::unexpected_eof()),
+        };
+        
+        // The ninth byte contributes all 8 bits
+        if i == 8 {
+            return Ok(((result << 8) | byte as u64, 9));
+        }
+        
+        result = (result << 7) | (byte & 0x7F) as u64;
+        
+        // Check if done
+        if byte & 0x80 == 0 {
+            return Ok((result, i + 1));
+        }
+    }
+    
+    unreachable!()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_known_encodings() {
+        let cases: [(u64, &[u8]); 9] = [
+            (0, &[0x00]),
+            (127, &[0x7F]),
+            (128, &[0x81, 0x00]),
+            (240, &[0x81, 0x70]),
+            (16383, &[0xFF, 0x7F]),
+            (16384, &[0x81, 0x80, 0x00]),
+            ((1 << 56) - 1, &[0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x7F]),
+            (1 << 56, &[0x80, 0xC0, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x00]),
+            (u64::MAX, &[0xFF; 9]),
+        ];
+        
+        for (value, expected) in cases {
+            let mut buf = [0u8; 9];
+            let bytes_written = encode_sqlite(value, &mut buf).unwrap();
+            assert_eq!(&buf[..bytes_written], expected, "Encoding mismatch for {}", value);
+            assert_eq!(sqlite_size(value), expected.len());
+            assert_eq!(decode_sqlite(expected), Ok((value, expected.len())));
+        }
+    }
+    
+    #[test]
+    fn test_nine_byte_values() {
+        let mut buf = [0u8; 9];
+        
+        for value in [1u64 << 63, (1 << 56) | 0xAB, u64::MAX - 1] {
+            assert_eq!(encode_sqlite(value, &mut buf), Ok(9));
+            // Eight bytes of 7 bits and a full final byte
+            assert!(buf[..8].iter().all(|&byte| byte & 0x80 != 0));
+            assert_eq!(buf[8], value as u8);
+            assert_eq!(decode_sqlite(&buf), Ok((value, 9)));
+        }
+        
+        // The ninth byte ends the value even with its high bit set
+        assert_eq!(decode_sqlite(&[0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0xFF, 0x01]), Ok((0xFF, 9)));
+    }
+    
+    #[test]
+    fn test_errors() {
+        assert_eq!(decode_sqlite(&[]), Err(Error::InputTooShort));
+        assert_eq!(decode_sqlite(&[0x81]), Err(Error::UnexpectedEof));
+        assert_eq!(decode_sqlite(&[0xFF; 8]), Err(Error::UnexpectedEof));
+        
+        let mut small = [0u8; 8];
+        assert_eq!(
+            encode_sqlite(u64::MAX, &mut small),
+            Err(Error::BufferTooSmall { needed: 9, actual: 8 })
+        );
+    }
+}
@@ -0,0 +1,97 @@
+use crate::error::Error;
+use crate::traits::{VarInt, VarIntOps};
+use crate::encoding::push_byte;
+
+/// Incremental decoder for varints that arrive in arbitrary chunks
+///
+/// Bytes are fed one at a time; the partial value and shift are kept between calls.
+/// After a value completes or an error is returned, the decoder starts over.
+pub struct StreamDecoder<T: VarInt = u64> {
+    result: T::Unsigned,
+    shift: usize,
+}
+
+impl<T: VarInt> StreamDecoder<T> {
+    /// Creates a new decoder with no bytes accumulated
+    pub fn new() -> Self {
+        StreamDecoder {
+            result: T::Unsigned::from_byte(0, 0),
+            shift: 0,
+        }
+    }
+    
+    /// Discards any partially decoded value
+    pub fn reset(&mut self) {
+        self.result = T::Unsigned::from_byte(0, 0);
+        self.shift = 0;
+    }
+    
+    /// Returns true if bytes of an unfinished value have been fed
+    pub fn is_partial(&self) -> bool {
+        self.shift > 0
+    }
+    
+    /// Feeds the next byte of the stream
+    ///
+    /// Returns `Ok(None)` while more bytes are needed and `Ok(Some(value))`
+    /// once the continuation bit clears
+    ///
+    /// # Errors
+    /// * Returns `Error::Overflow` if the value does not fit in `T`
+    /// * Returns `Error::InvalidEncoding` if the value cannot be represented in `T`
+    ///
+    /// The decoder is reset after an error
+    pub fn feed(&mut self, byte: u8) -> Result<Option<T>, Error> {
+        let step = push_byte(&mut self.result, byte, &mut self.shift);
+        
+        // Start over once a value completes or fails
+        if !matches!(step, Ok(None)) {
+            self.reset();
+        }
+        
+        step
+    }
+}
+
+impl<T: VarInt> Default for StreamDecoder<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_feed_one_byte_at_a_time() {
+        let mut decoder = StreamDecoder::<u64>::new();
+        assert_eq!(decoder.feed(0x80), Ok(None));
+        assert!(decoder.is_partial());
+        assert_eq!(decoder.feed(0x80), Ok(None));
+        assert_eq!(decoder.feed(0x01), Ok(Some(16384)));
+        assert!(!decoder.is_partial());
+        
+        // The decoder starts over for the next value
+        assert_eq!(decoder.feed(0x05), Ok(Some(5)));
+    }
+    
+    #[test]
+    fn test_reset_and_overflow() {
+        let mut decoder = StreamDecoder::<u16>::new();
+        assert_eq!(decoder.feed(0xFF), Ok(None));
+        decoder.reset();
+        assert_eq!(decoder.feed(0x2A), Ok(Some(42)));
+        
+        // Bits beyond u16 are rejected
+        assert_eq!(decoder.feed(0xFF), Ok(None));
+        assert_eq!(decoder.feed(0xFF), Ok(None));
+        assert_eq!(decoder.feed(0x04), Err(Error::Overflow { bytes_read: 3 }));
+        
+        // Too many continuation bytes are rejected
+        assert_eq!(decoder.feed(0x80), Ok(None));
+        assert_eq!(decoder.feed(0x80), Ok(None));
+        assert_eq!(decoder.feed(0x80), Err(Error::Overflow { bytes_read: 3 }));
+        assert!(!decoder.is_partial());
+    }
+}
@@ -1,14 +1,18 @@
 #[cfg(test)]
+#[allow(clippy::module_inception)]
 mod tests {
     // Enable standard library in tests
     extern crate std;
     use self::std::vec::Vec;
     
-    use crate::encoding::{encode, decode, varint_size};
-    use crate::zigzag::{encode_zigzag, decode_zigzag};
-    use crate::batch::{VarIntEncoder, VarIntDecoder};
-    use crate::iter::{bytes_of, values_from};
+    use crate::encoding::{MAX_VARINT_LEN, Endianness, encode_with_endian, decode_with_endian, encode, encode_at, encode_array, encode_u64_const, encode_max_len, decode, decode_narrowing, decode_saturating, decode_split, decode_after_sentinel, decode_from_iter, decode_canonical, decode_limited, varint_size, byte_fits};
+    use crate::zigzag::{zigzag_encode_value, zigzag_decode_value, zigzag_size, encode_zigzag, decode_zigzag, decode_zigzag_split};
+    use crate::batch::{encode_batch, decode_batch_trusted, VarIntEncoder, VarIntDecoder, VarIntSink, encode_zigzag_batch, decode_zigzag_batch, zigzag_batch_size, decode_packed, decode_until, read_at, validate_stream, fold_values, sum_values, decode_recoverable, count_varints, size_histogram};
+    use crate::iter::{bytes_of, encode_iter, values_from, indexed_values_from, bytes_of_zigzag, zigzag_values_from};
     use crate::Error;
+    use crate::traits::{VarInt, VarIntOps};
+    #[cfg(feature = "alloc")]
+    use crate::batch::{encode_to_vec, encode_batch_to_vec, decode_all};
 
     #[test]
     fn test_encode_decode_u64() {
@@ -166,17 +170,114 @@ mod tests {
     
     #[test]
     fn test_decode_input_too_short() {
+        // An empty buffer needs more data
+        assert_eq!(decode::<u64>(&[]), Err(Error::InputTooShort));
+        
         // Create an incomplete varint - highest bit is 1 indicating more bytes follow, but none provided
         let buf = [0x80];
         
         let result = decode::<u64>(&buf);
         assert!(result.is_err());
         
-        if let Err(Error::InputTooShort) = result {
+        if let Err(Error::UnexpectedEof) = result {
             // Test passed
         } else {
-            panic!("Expected InputTooShort error");
+            panic!("Expected UnexpectedEof error");
         }
+        assert_eq!(decode::<u64>(&[0xFF, 0xFF]), Err(Error::UnexpectedEof));
+    }
+    
+    #[test]
+    fn test_decode_canonical() {
+        // Overlong forms are rejected
+        assert_eq!(decode_canonical::<u64>(&[0x80, 0x00]), Err(Error::InvalidEncoding { offset: 1 }));
+        assert_eq!(decode_canonical::<u64>(&[0xFF, 0x00]), Err(Error::InvalidEncoding { offset: 1 }));
+        assert_eq!(decode_canonical::<u32>(&[0x80, 0x80, 0x00]), Err(Error::InvalidEncoding { offset: 1 }));
+        
+        // The lenient decoder still accepts them
+        assert_eq!(decode::<u64>(&[0x80, 0x00]), Ok((0, 2)));
+        assert_eq!(decode::<u64>(&[0xFF, 0x00]), Ok((127, 2)));
+        
+        // Minimal forms pass through
+        assert_eq!(decode_canonical::<u64>(&[0x00]), Ok((0, 1)));
+        assert_eq!(decode_canonical::<u64>(&[0x7F]), Ok((127, 1)));
+        assert_eq!(decode_canonical::<u64>(&[0x80, 0x01]), Ok((128, 2)));
+        
+        let mut buf = [0u8; 10];
+        let bytes_written = encode(u64::MAX, &mut buf).unwrap();
+        assert_eq!(decode_canonical::<u64>(&buf), Ok((u64::MAX, bytes_written)));
+        
+        // Errors from the underlying decode are preserved
+        assert_eq!(decode_canonical::<u64>(&[0x80]), Err(Error::UnexpectedEof));
+    }
+    
+    #[test]
+    fn test_decode_limited() {
+        let mut buf = [0u8; 10];
+        let bytes_written = encode(u64::MAX, &mut buf).unwrap();
+        assert_eq!(bytes_written, 10);
+        assert_eq!(decode_limited::<u64>(&buf, 3), Err(Error::LimitExceeded));
+        assert_eq!(decode_limited::<u64>(&buf, 10), Ok((u64::MAX, 10)));
+        
+        // A short encoding passes the same limit
+        let bytes_written = encode(300u64, &mut buf).unwrap();
+        assert_eq!(decode_limited::<u64>(&buf, 3), Ok((300, bytes_written)));
+        assert_eq!(decode_limited::<u64>(&buf[..bytes_written], 3), Ok((300, bytes_written)));
+        
+        // Running out of input inside the limit is still UnexpectedEof
+        assert_eq!(decode_limited::<u64>(&[0x80, 0x80], 3), Err(Error::UnexpectedEof));
+        assert_eq!(decode_limited::<u64>(&[], 3), Err(Error::InputTooShort));
+    }
+    
+    #[test]
+    fn test_decode_rejects_bits_beyond_type_width() {
+        // Maximum values still decode
+        assert_eq!(decode::<u8>(&[0xFF, 0x01]), Ok((u8::MAX, 2)));
+        assert_eq!(decode::<u16>(&[0xFF, 0xFF, 0x03]), Ok((u16::MAX, 3)));
+        assert_eq!(decode::<u32>(&[0xFF, 0xFF, 0xFF, 0xFF, 0x0F]), Ok((u32::MAX, 5)));
+        let mut u64_max = [0xFFu8; 10];
+        u64_max[9] = 0x01;
+        assert_eq!(decode::<u64>(&u64_max), Ok((u64::MAX, 10)));
+        let mut u128_max = [0xFFu8; 19];
+        u128_max[18] = 0x03;
+        assert_eq!(decode::<u128>(&u128_max), Ok((u128::MAX, 19)));
+        assert_eq!(decode::<i8>(&[0xFF, 0x01]), Ok((-1i8, 2)));
+        
+        // One bit past the maximum overflows
+        assert_eq!(decode::<u8>(&[0xFF, 0x7F]), Err(Error::Overflow { bytes_read: 2 }));
+        assert_eq!(decode::<u8>(&[0x80, 0x02]), Err(Error::Overflow { bytes_read: 2 }));
+        assert_eq!(decode::<u16>(&[0xFF, 0xFF, 0x04]), Err(Error::Overflow { bytes_read: 3 }));
+        assert_eq!(decode::<u32>(&[0xFF, 0xFF, 0xFF, 0xFF, 0x10]), Err(Error::Overflow { bytes_read: 5 }));
+        u64_max[9] = 0x02;
+        assert_eq!(decode::<u64>(&u64_max), Err(Error::Overflow { bytes_read: 10 }));
+        u128_max[18] = 0x04;
+        assert_eq!(decode::<u128>(&u128_max), Err(Error::Overflow { bytes_read: 19 }));
+        assert_eq!(decode::<i16>(&[0x80, 0x80, 0x04]), Err(Error::Overflow { bytes_read: 3 }));
+    }
+    
+    #[test]
+    fn test_max_encoded_len() {
+        assert_eq!(u8::MAX_ENCODED_LEN, 2);
+        assert_eq!(u16::MAX_ENCODED_LEN, 3);
+        assert_eq!(u32::MAX_ENCODED_LEN, 5);
+        assert_eq!(u64::MAX_ENCODED_LEN, 10);
+        assert_eq!(u128::MAX_ENCODED_LEN, 19);
+        assert_eq!(i64::MAX_ENCODED_LEN, u64::MAX_ENCODED_LEN);
+        
+        // The extreme values of every type fit exactly
+        macro_rules! check_fits {
+            ($($type:ty),*) => {
+                $(
+                    for value in [<$type>::MIN, <$type>::MAX] {
+                        let mut buf = [0u8; <$type>::MAX_ENCODED_LEN];
+                        assert!(encode(value, &mut buf).unwrap() <= <$type>::MAX_ENCODED_LEN);
+                    }
+                    let all_ones = !(0 as $type);
+                    assert_eq!(varint_size(all_ones), <$type>::MAX_ENCODED_LEN);
+                )*
+            };
+        }
+        check_fits!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128);
     }
     
     #[test]
@@ -292,4 +393,1274 @@ mod tests {
         
         assert_eq!(values_i32, decoded);
     }
-} 
\ No newline at end of file
+    
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_vec_helpers() {
+        // Single value vec is sized exactly
+        for &value in &[0u64, 127, 128, 16384, u64::MAX] {
+            let bytes = encode_to_vec(value);
+            assert_eq!(bytes.len(), varint_size(value));
+            assert_eq!(decode::<u64>(&bytes).unwrap(), (value, bytes.len()));
+        }
+        
+        // Batch vec length matches the sum of individual sizes
+        let values = [1i32, -1, 300, -70000, i32::MIN];
+        let bytes = encode_batch_to_vec(&values);
+        let expected: usize = values.iter().map(|&v| varint_size(v)).sum();
+        assert_eq!(bytes.len(), expected);
+        assert_eq!(bytes.capacity(), expected);
+        
+        let decoded: Vec<i32> = decode_all(&bytes).unwrap();
+        assert_eq!(decoded, values.to_vec());
+        
+        // The first decode error is surfaced
+        assert_eq!(decode_all::<u64>(&[0x01, 0x80]), Err(Error::UnexpectedEof));
+    }
+    
+    #[test]
+    fn test_decoder_peek() {
+        let mut buffer = [0u8; 20];
+        let mut encoder = VarIntEncoder::<u32>::new(&mut buffer);
+        encoder.write(300).unwrap();
+        encoder.write_zigzag(-5i32).unwrap();
+        let bytes_written = encoder.position();
+        
+        let mut decoder = VarIntDecoder::<u32>::new(&buffer[..bytes_written]);
+        assert_eq!(decoder.peek(), Ok(300));
+        assert_eq!(decoder.peek(), Ok(300));
+        assert_eq!(decoder.peek_with_size(), Ok((300, 2)));
+        assert_eq!(decoder.position(), 0);
+        
+        assert_eq!(decoder.read(), Ok(300));
+        assert_eq!(decoder.peek_zigzag::<i32>(), Ok(-5));
+        assert_eq!(decoder.position(), 2);
+        assert_eq!(decoder.read_zigzag::<i32>(), Ok(-5));
+        
+        assert_eq!(decoder.peek(), Err(Error::InputTooShort));
+        assert_eq!(decoder.peek_zigzag::<i32>(), Err(Error::InputTooShort));
+    }
+    
+    #[test]
+    fn test_count_varints() {
+        assert_eq!(count_varints(&[]), Ok(0));
+        
+        let values = [0u64, 127, 128, 16384, u64::MAX];
+        let mut buffer = [0u8; 50];
+        let mut encoder = VarIntEncoder::new(&mut buffer);
+        let bytes_written = encoder.write_batch(&values).unwrap();
+        assert_eq!(count_varints(&buffer[..bytes_written]), Ok(values.len()));
+        
+        // Truncated trailing varint
+        assert_eq!(count_varints(&buffer[..bytes_written - 1]), Err(Error::UnexpectedEof));
+        assert_eq!(count_varints(&[0x01, 0x80]), Err(Error::UnexpectedEof));
+    }
+    
+    #[test]
+    fn test_pointer_sized_integers() {
+        let mut buf = [0u8; usize::MAX_ENCODED_LEN];
+        
+        for &value in &[0usize, 127, 128, usize::MAX] {
+            let bytes_written = encode(value, &mut buf).unwrap();
+            assert_eq!(bytes_written, varint_size(value));
+            assert_eq!(decode::<usize>(&buf), Ok((value, bytes_written)));
+        }
+        
+        for &value in &[0isize, -1, isize::MIN, isize::MAX] {
+            let bytes_written = encode_zigzag(value, &mut buf).unwrap();
+            assert_eq!(decode_zigzag::<isize>(&buf), Ok((value, bytes_written)));
+            
+            let bytes_written = encode(value, &mut buf).unwrap();
+            assert_eq!(decode::<isize>(&buf), Ok((value, bytes_written)));
+        }
+        
+        // The encoding width follows the target's pointer width
+        #[cfg(target_pointer_width = "64")]
+        {
+            assert_eq!(usize::MAX_ENCODED_LEN, 10);
+            assert_eq!(varint_size(usize::MAX), varint_size(u64::MAX));
+        }
+        #[cfg(target_pointer_width = "32")]
+        {
+            assert_eq!(usize::MAX_ENCODED_LEN, 5);
+            assert_eq!(varint_size(usize::MAX), varint_size(u32::MAX));
+        }
+    }
+    
+    #[test]
+    fn test_zigzag_iterators() {
+        let values = [0i32, -1, 1, -64, 64, i32::MIN, i32::MAX];
+        let mut buf = [0u8; 10];
+        let mut stream = Vec::new();
+        
+        for &value in &values {
+            let bytes_written = encode_zigzag(value, &mut buf).unwrap();
+            
+            let encoder = bytes_of_zigzag(value);
+            assert_eq!(encoder.size(), bytes_written);
+            
+            let bytes: Vec<u8> = encoder.collect();
+            assert_eq!(bytes, &buf[..bytes_written]);
+            stream.extend_from_slice(&bytes);
+        }
+        
+        let decoded: Result<Vec<i32>, _> = zigzag_values_from(&stream).collect();
+        assert_eq!(decoded.unwrap(), values.to_vec());
+        
+        // Errors are surfaced and end the iteration
+        let mut iter = zigzag_values_from::<i32>(&[0x02, 0x80]);
+        assert_eq!(iter.next(), Some(Ok(1)));
+        assert_eq!(iter.next(), Some(Err(Error::UnexpectedEof)));
+        assert_eq!(iter.next(), None);
+    }
+    
+    #[test]
+    fn test_nonzero_types() {
+        use core::num::{NonZeroU32, NonZeroU64, NonZeroI32};
+        
+        let mut buf = [0u8; 10];
+        let one = NonZeroU64::new(1).unwrap();
+        assert_eq!(encode(one, &mut buf), Ok(1));
+        assert_eq!(buf[0], 0x01);
+        assert_eq!(decode::<NonZeroU64>(&buf), Ok((one, 1)));
+        
+        let big = NonZeroU32::new(300).unwrap();
+        let bytes_written = encode(big, &mut buf).unwrap();
+        assert_eq!(bytes_written, varint_size(300u32));
+        assert_eq!(decode::<NonZeroU32>(&buf[..bytes_written]), Ok((big, 2)));
+        
+        // Zero cannot be represented, whatever its encoding
+        assert_eq!(decode::<NonZeroU32>(&[0x00]), Err(Error::InvalidEncoding { offset: 0 }));
+        assert_eq!(decode::<NonZeroU32>(&[0x80, 0x00]), Err(Error::InvalidEncoding { offset: 0 }));
+        let decoded: Vec<_> = values_from::<NonZeroU32>(&[0x01, 0x00]).collect();
+        assert_eq!(decoded, [Ok(NonZeroU32::new(1).unwrap()), Err(Error::InvalidEncoding { offset: 0 })]);
+        assert_eq!(decode_from_iter::<NonZeroU32, _>(&mut [0x00].into_iter()), Err(Error::InvalidEncoding { offset: 0 }));
+        assert_eq!(decode_with_endian::<NonZeroU32>(&[0x00], Endianness::Big), Err(Error::InvalidEncoding { offset: 0 }));
+        assert_eq!(decode_canonical::<NonZeroU32>(&[0x00]), Err(Error::InvalidEncoding { offset: 0 }));
+        let mut decoder = VarIntDecoder::<NonZeroU32>::new(&[0x00]);
+        assert_eq!(decoder.read(), Err(Error::InvalidEncoding { offset: 0 }));
+        
+        // Signed non-zero types support zigzag
+        let minus_one = NonZeroI32::new(-1).unwrap();
+        assert_eq!(encode_zigzag(minus_one, &mut buf), Ok(1));
+        assert_eq!(buf[0], 0x01);
+        assert_eq!(decode_zigzag::<NonZeroI32>(&buf), Ok((minus_one, 1)));
+        assert_eq!(decode_zigzag::<NonZeroI32>(&[0x00]), Err(Error::InvalidEncoding { offset: 0 }));
+    }
+    
+    #[test]
+    fn test_encode_array() {
+        let (a, n) = encode_array(300u32);
+        assert_eq!(&a[..n], &[0xAC, 0x02]);
+        
+        let (a, n) = encode_array(0u8);
+        assert_eq!(&a[..n], &[0x00]);
+        
+        let (a, n) = encode_array(u128::MAX);
+        assert_eq!(n, u128::MAX_ENCODED_LEN);
+        assert_eq!(decode::<u128>(&a[..n]), Ok((u128::MAX, n)));
+        
+        let mut buf = [0u8; 10];
+        for value in [-1i64, i64::MIN, 42] {
+            let (a, n) = encode_array(value);
+            let bytes_written = encode(value, &mut buf).unwrap();
+            assert_eq!(&a[..n], &buf[..bytes_written]);
+        }
+    }
+    
+    #[test]
+    fn test_encode_iter() {
+        let values = [1u64, 128, 16384];
+        let encoder = encode_iter(values.into_iter());
+        assert_eq!(encoder.size_hint(), (3, Some(3 * u64::MAX_ENCODED_LEN)));
+        
+        let bytes: Vec<u8> = encoder.collect();
+        assert_eq!(bytes, [0x01, 0x80, 0x01, 0x80, 0x80, 0x01]);
+        
+        let decoded: Result<Vec<u64>, _> = values_from(&bytes).collect();
+        assert_eq!(decoded.unwrap(), values.to_vec());
+        
+        // Empty sources produce no bytes
+        assert_eq!(encode_iter(core::iter::empty::<u32>()).count(), 0);
+    }
+    
+    #[test]
+    fn test_encoder_decoder_reset() {
+        let mut buffer = [0u8; 10];
+        let mut encoder = VarIntEncoder::<u32>::new(&mut buffer);
+        assert_eq!(encoder.write_batch(&[16384, 300]), Ok(5));
+        assert_eq!(encoder.position(), 5);
+        
+        // The second batch overwrites the first from the start
+        encoder.reset();
+        assert_eq!(encoder.position(), 0);
+        assert_eq!(encoder.remaining(), 10);
+        assert_eq!(encoder.write_batch(&[5, 1]), Ok(2));
+        assert_eq!(encoder.position(), 2);
+        
+        let mut other = [0u8; 2];
+        encoder.reset_with(&mut other);
+        assert_eq!(encoder.position(), 0);
+        assert_eq!(encoder.remaining(), 2);
+        assert_eq!(encoder.write(127), Ok(1));
+        
+        assert_eq!(other, [0x7F, 0x00]);
+        // Bytes past the last write are left as they were
+        assert_eq!(&buffer[..5], &[0x05, 0x01, 0x01, 0xAC, 0x02]);
+        
+        let mut decoder = VarIntDecoder::<u32>::new(&buffer[..2]);
+        assert_eq!(decoder.read(), Ok(5));
+        assert_eq!(decoder.read(), Ok(1));
+        decoder.reset();
+        assert_eq!(decoder.position(), 0);
+        assert_eq!(decoder.read(), Ok(5));
+    }
+    
+    #[test]
+    fn test_raw_bytes_between_varints() {
+        let payload = [0xABu8; 16];
+        let mut buffer = [0u8; 24];
+        let mut encoder = VarIntEncoder::<u32>::new(&mut buffer);
+        encoder.write(300).unwrap();
+        assert_eq!(encoder.write_raw(&payload), Ok(16));
+        encoder.write(7).unwrap();
+        assert_eq!(encoder.write_raw(&[]), Ok(0));
+        let bytes_written = encoder.position();
+        assert_eq!(bytes_written, 19);
+        
+        // A block that does not fit leaves the encoder untouched
+        assert_eq!(
+            encoder.write_raw(&payload),
+            Err(Error::BufferTooSmall { needed: 35, actual: 24 })
+        );
+        assert_eq!(encoder.position(), 19);
+        
+        let mut decoder = VarIntDecoder::<u32>::new(&buffer[..bytes_written]);
+        assert_eq!(decoder.read(), Ok(300));
+        assert_eq!(decoder.read_raw(16), Ok(&payload[..]));
+        assert_eq!(decoder.read(), Ok(7));
+        
+        assert_eq!(decoder.read_raw(1), Err(Error::InputTooShort));
+        assert_eq!(decoder.read_raw(0), Ok(&[][..]));
+        assert_eq!(decoder.position(), bytes_written);
+    }
+    
+    #[test]
+    fn test_decode_packed() {
+        // Payload of the packed repeated field [3, 270, 86942] from the protobuf encoding guide
+        let packed = [0x06, 0x03, 0x8E, 0x02, 0x9E, 0xA7, 0x05];
+        let mut out = [0u32; 4];
+        assert_eq!(decode_packed(&packed, &mut out), Ok(3));
+        assert_eq!(&out[..3], &[3, 270, 86942]);
+        
+        // Bytes after the declared length are not part of the field
+        let mut followed = [0u8; 8];
+        followed[..7].copy_from_slice(&packed);
+        followed[7] = 0x01;
+        assert_eq!(decode_packed(&followed, &mut out), Ok(3));
+        
+        assert_eq!(decode_packed::<u32>(&[0x00], &mut out), Ok(0));
+        
+        // Declared length longer than the buffer
+        assert_eq!(decode_packed(&packed[..6], &mut out), Err(Error::InputTooShort));
+        // Declared length cutting the last value short
+        assert_eq!(decode_packed(&[0x02, 0x03, 0x8E, 0x02], &mut out), Err(Error::UnexpectedEof));
+        
+        let mut small = [0u32; 2];
+        assert_eq!(
+            decode_packed(&packed, &mut small),
+            Err(Error::BufferTooSmall { needed: 3, actual: 2 })
+        );
+    }
+    
+    #[test]
+    fn test_bytes_iter_to_array() {
+        let mut buf = [0u8; 19];
+        let bytes_written = encode(300u32, &mut buf).unwrap();
+        let (a, n) = bytes_of(300u32).to_array();
+        assert_eq!(&a[..n], &buf[..bytes_written]);
+        
+        let (a, n) = bytes_of(u128::MAX).to_array();
+        assert_eq!(n, 19);
+        assert_eq!(decode::<u128>(&a[..n]), Ok((u128::MAX, 19)));
+        
+        // Only the bytes not yet taken are collected
+        let mut iter = bytes_of(16384u64);
+        iter.next();
+        let (a, n) = iter.to_array();
+        assert_eq!(&a[..n], &[0x80, 0x01]);
+    }
+    
+    #[test]
+    fn test_varint_sink() {
+        let mut buffer = [0u8; 5];
+        let mut sink = VarIntSink::new(VarIntEncoder::<u32>::new(&mut buffer));
+        sink.extend([1u32, 300].iter().copied());
+        assert_eq!(sink.result(), Ok(3));
+        
+        // 16384 needs three bytes but only two are left; later values are skipped
+        sink.extend([16384u32, 1, 1]);
+        assert_eq!(sink.bytes_written(), 3);
+        assert_eq!(sink.result(), Err(Error::BufferTooSmall { needed: 3, actual: 2 }));
+        sink.extend([1u32]);
+        assert_eq!(sink.bytes_written(), 3);
+        
+        let encoder = sink.into_encoder();
+        assert_eq!(encoder.position(), 3);
+        assert_eq!(&buffer[..3], &[0x01, 0xAC, 0x02]);
+    }
+    
+    #[test]
+    fn test_zigzag_batch_functions() {
+        let values = [-1i32, 0, 1, i32::MIN, i32::MAX];
+        let mut buffer = [0u8; 30];
+        
+        let bytes_written = encode_zigzag_batch(&values, &mut buffer).unwrap();
+        assert_eq!(bytes_written, 1 + 1 + 1 + 5 + 5);
+        
+        let mut decoded = [0i32; 5];
+        assert_eq!(decode_zigzag_batch(&buffer[..bytes_written], &mut decoded), Ok(5));
+        assert_eq!(decoded, values);
+        
+        assert!(encode_zigzag_batch(&values, &mut buffer[..10]).is_err());
+    }
+    
+    #[test]
+    fn test_read_batch_exact() {
+        let mut buffer = [0u8; 10];
+        let bytes_written = encode_batch(&[1, 300, 16384], &mut buffer).unwrap();
+        
+        // Exact fit
+        let mut values = [0u64; 3];
+        let mut decoder = VarIntDecoder::<u64>::new(&buffer[..bytes_written]);
+        assert_eq!(decoder.read_batch_exact(&mut values), Ok(()));
+        assert_eq!(values, [1, 300, 16384]);
+        assert_eq!(decoder.position(), bytes_written);
+        
+        // Trailing bytes after the requested values
+        let mut short = [0u64; 2];
+        let mut decoder = VarIntDecoder::<u64>::new(&buffer[..bytes_written]);
+        assert_eq!(decoder.read_batch_exact(&mut short), Err(Error::TrailingData));
+        assert_eq!(decoder.position(), 0);
+        
+        // Fewer values than requested
+        let mut long = [0u64; 4];
+        let mut decoder = VarIntDecoder::<u64>::new(&buffer[..bytes_written]);
+        assert_eq!(decoder.read_batch_exact(&mut long), Err(Error::InputTooShort));
+        assert_eq!(decoder.position(), 0);
+        
+        // Last value cut off
+        let mut decoder = VarIntDecoder::<u64>::new(&buffer[..bytes_written - 1]);
+        assert_eq!(decoder.read_batch_exact(&mut values), Err(Error::UnexpectedEof));
+    }
+    
+    #[test]
+    fn test_remaining_count() {
+        let mut buffer = [0u8; 20];
+        let bytes_written = encode_batch(&[1, 300, 16384, 0, u64::MAX], &mut buffer).unwrap();
+        
+        let mut decoder = VarIntDecoder::<u64>::new(&buffer[..bytes_written]);
+        assert_eq!(decoder.remaining_count(), Ok(5));
+        decoder.read().unwrap();
+        decoder.read().unwrap();
+        assert_eq!(decoder.remaining_count(), Ok(3));
+        assert_eq!(decoder.position(), 3);
+        
+        let mut rest = [0u64; 3];
+        assert_eq!(decoder.read_batch(&mut rest), Ok(3));
+        assert_eq!(decoder.remaining_count(), Ok(0));
+        
+        let decoder = VarIntDecoder::<u64>::new(&buffer[..bytes_written - 1]);
+        assert_eq!(decoder.remaining_count(), Err(Error::UnexpectedEof));
+    }
+    
+    #[test]
+    fn test_encode_size_boundaries() {
+        let cases: [(u64, &[u8]); 4] = [
+            (127, &[0x7F]),
+            (128, &[0x80, 0x01]),
+            (16383, &[0xFF, 0x7F]),
+            (16384, &[0x80, 0x80, 0x01]),
+        ];
+        
+        for (value, expected) in cases {
+            let mut buf = [0u8; 10];
+            assert_eq!(encode(value, &mut buf), Ok(expected.len()));
+            assert_eq!(&buf[..expected.len()], expected);
+            assert_eq!(encode(value as u16, &mut buf), Ok(expected.len()));
+            assert_eq!(&buf[..expected.len()], expected);
+            
+            // One byte short of the encoding
+            assert_eq!(
+                encode(value, &mut buf[..expected.len() - 1]),
+                Err(Error::BufferTooSmall { needed: expected.len(), actual: expected.len() - 1 })
+            );
+        }
+        
+        // u8 values above 127 take the two-byte path
+        let mut buf = [0u8; 2];
+        assert_eq!(encode(u8::MAX, &mut buf), Ok(2));
+        assert_eq!(buf, [0xFF, 0x01]);
+        assert_eq!(encode(-1i8, &mut buf[..1]), Err(Error::BufferTooSmall { needed: 2, actual: 1 }));
+    }
+    
+    #[test]
+    fn test_decode_batch_trusted() {
+        let values: Vec<u32> = (0..100u32).map(|i| i.wrapping_mul(2_654_435_761) >> (i % 32)).collect();
+        let mut buffer = [0u8; 500];
+        let bytes_written = VarIntEncoder::new(&mut buffer).write_batch(&values).unwrap();
+        
+        let mut decoded = [0u32; 100];
+        assert_eq!(decode_batch_trusted(&buffer[..bytes_written], &mut decoded), Ok(100));
+        assert_eq!(&decoded[..], &values[..]);
+        
+        // Stops when out is full, like read_batch
+        let mut few = [0u32; 3];
+        assert_eq!(decode_batch_trusted(&buffer[..bytes_written], &mut few), Ok(3));
+        assert_eq!(few, [values[0], values[1], values[2]]);
+        
+        assert_eq!(decode_batch_trusted::<u32>(&[], &mut few), Ok(0));
+        assert_eq!(decode_batch_trusted(&buffer[..bytes_written - 1], &mut decoded), Err(Error::UnexpectedEof));
+        
+        // Overlong varints are still rejected, with or without a full window behind them
+        let overlong = [0x80, 0x80, 0x80, 0x80, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00];
+        assert_eq!(decode_batch_trusted::<u32>(&overlong, &mut few), Err(Error::Overflow { bytes_read: 5 }));
+        assert_eq!(decode_batch_trusted::<u32>(&overlong[..6], &mut few), Err(Error::Overflow { bytes_read: 5 }));
+    }
+    
+    #[test]
+    fn test_decode_from_iter() {
+        let mut iter = [0x80, 0x80, 0x01].into_iter();
+        assert_eq!(decode_from_iter::<u64, _>(&mut iter), Ok(16384));
+        assert_eq!(iter.next(), None);
+        
+        // The iterator is left just past each value
+        let mut iter = [0xAC, 0x02, 0x05, 0xFF].into_iter();
+        assert_eq!(decode_from_iter::<u32, _>(&mut iter), Ok(300));
+        assert_eq!(decode_from_iter::<u32, _>(&mut iter), Ok(5));
+        assert_eq!(decode_from_iter::<u32, _>(&mut iter), Err(Error::UnexpectedEof));
+        assert_eq!(decode_from_iter::<u32, _>(&mut iter), Err(Error::InputTooShort));
+        
+        // Composes with bytes_of
+        let mut iter = bytes_of(u128::MAX);
+        assert_eq!(decode_from_iter::<u128, _>(&mut iter), Ok(u128::MAX));
+        
+        let mut iter = [0xFF, 0xFF, 0x04].into_iter();
+        assert_eq!(decode_from_iter::<u16, _>(&mut iter), Err(Error::Overflow { bytes_read: 3 }));
+    }
+    
+    #[test]
+    fn test_encoder_written_and_remaining_mut() {
+        let mut buffer = [0u8; 16];
+        let mut encoder = VarIntEncoder::<u32>::new(&mut buffer);
+        encoder.write(300).unwrap();
+        encoder.write(5).unwrap();
+        
+        assert_eq!(encoder.written().len(), encoder.position());
+        assert_eq!(encoder.written(), &[0xAC, 0x02, 0x05]);
+        assert_eq!(encoder.remaining_mut().len(), 13);
+        
+        // Hand the tail to a non-varint writer
+        encoder.remaining_mut()[..2].copy_from_slice(b"hi");
+        assert_eq!(&buffer[3..5], b"hi");
+    }
+    
+    #[test]
+    fn test_read_batch_checked() {
+        let mut buffer = [0u8; 16];
+        let bytes_written = encode_batch(&[1, 300, 3, 70000, 5], &mut buffer).unwrap();
+        let data = &buffer[..bytes_written];
+        
+        // Lenient: stops once out is full
+        let mut out = [0u64; 2];
+        let mut decoder = VarIntDecoder::<u64>::new(data);
+        assert_eq!(decoder.read_batch(&mut out), Ok(2));
+        assert_eq!(out, [1, 300]);
+        assert_eq!(decoder.position(), 3);
+        
+        // Strict: leftover input is an error and the position is restored
+        let mut decoder = VarIntDecoder::<u64>::new(data);
+        assert_eq!(
+            decoder.read_batch_checked(&mut out),
+            Err(Error::BufferTooSmall { needed: 5, actual: 2 })
+        );
+        assert_eq!(decoder.position(), 0);
+        
+        // An empty out no longer silently reads nothing
+        assert_eq!(decoder.read_batch(&mut []), Ok(0));
+        assert_eq!(
+            decoder.read_batch_checked(&mut []),
+            Err(Error::BufferTooSmall { needed: 5, actual: 0 })
+        );
+        
+        let mut out = [0u64; 8];
+        assert_eq!(decoder.read_batch_checked(&mut out), Ok(5));
+        assert_eq!(&out[..5], &[1, 300, 3, 70000, 5]);
+        
+        // Trailing partial value
+        let mut decoder = VarIntDecoder::<u64>::new(&[0x01, 0x80]);
+        assert_eq!(decoder.read_batch_checked(&mut out), Err(Error::UnexpectedEof));
+        assert_eq!(decoder.position(), 0);
+    }
+    
+    #[test]
+    fn test_encode_max_len() {
+        let mut buffer = [0u8; 10];
+        
+        // 16384 needs 3 bytes
+        assert_eq!(
+            encode_max_len(16384u32, &mut buffer, 2),
+            Err(Error::ValueTooLarge { size: 3, max_len: 2 })
+        );
+        assert_eq!(buffer, [0u8; 10]);
+        
+        assert_eq!(encode_max_len(16384u32, &mut buffer, 3), Ok(3));
+        assert_eq!(&buffer[..3], &[0x80, 0x80, 0x01]);
+        
+        // The buffer is still checked once the limit passes
+        assert_eq!(
+            encode_max_len(u64::MAX, &mut buffer[..4], 10),
+            Err(Error::BufferTooSmall { needed: 10, actual: 4 })
+        );
+    }
+    
+    #[test]
+    fn test_decode_split() {
+        let mut buffer = [0u8; 16];
+        let mut pos = encode(300u32, &mut buffer).unwrap();
+        pos += encode_zigzag(-2i64, &mut buffer[pos..]).unwrap();
+        pos += encode(7u8, &mut buffer[pos..]).unwrap();
+        
+        let rest = &buffer[..pos];
+        let (first, rest) = decode_split::<u32>(rest).unwrap();
+        let (second, rest) = decode_zigzag_split::<i64>(rest).unwrap();
+        let (third, rest) = decode_split::<u8>(rest).unwrap();
+        assert_eq!((first, second, third), (300, -2, 7));
+        assert!(rest.is_empty());
+        
+        assert_eq!(decode_split::<u32>(rest), Err(Error::InputTooShort));
+        assert_eq!(decode_zigzag_split::<i32>(&[0x80]), Err(Error::UnexpectedEof));
+    }
+    
+    #[test]
+    fn test_values_iter_count_remaining() {
+        let mut buffer = [0u8; 16];
+        let bytes_written = encode_batch(&[1, 300, 70000, 5], &mut buffer).unwrap();
+        
+        let mut iter = values_from::<u64>(&buffer[..bytes_written]);
+        assert_eq!(iter.count_remaining(), Ok(4));
+        assert_eq!(iter.next(), Some(Ok(1)));
+        assert_eq!(iter.count_remaining(), Ok(3));
+        assert_eq!(iter.position(), 1);
+        
+        let rest: Vec<u64> = iter.map(Result::unwrap).collect();
+        assert_eq!(rest, [300, 70000, 5]);
+        
+        // A malformed tail is reported rather than guessed at
+        let iter = values_from::<u64>(&[0x01, 0x80]);
+        assert_eq!(iter.count_remaining(), Err(Error::UnexpectedEof));
+    }
+    
+    #[test]
+    fn test_decoder_iter_shares_position() {
+        let mut buffer = [0u8; 16];
+        let bytes_written = encode_batch(&[1, 300, 70000, 5], &mut buffer).unwrap();
+        
+        let mut decoder = VarIntDecoder::<u64>::new(&buffer[..bytes_written]);
+        let first_two: Vec<u64> = decoder.iter().take(2).map(Result::unwrap).collect();
+        assert_eq!(first_two, [1, 300]);
+        assert_eq!(decoder.position(), 3);
+        
+        assert_eq!(decoder.read(), Ok(70000));
+        assert_eq!(decoder.iter().collect::<Vec<_>>(), [Ok(5)]);
+        assert_eq!(decoder.iter().next(), None);
+        
+        // Stops after the first error instead of repeating it
+        let mut decoder = VarIntDecoder::<u8>::new(&[0x01, 0xFF, 0x7F]);
+        assert_eq!(decoder.iter().collect::<Vec<_>>(), [Ok(1), Err(Error::Overflow { bytes_read: 2 })]);
+        assert_eq!(decoder.position(), 1);
+    }
+    
+    #[test]
+    fn test_write_batch_offsets() {
+        let mut buffer = [0u8; 16];
+        let mut offsets = [0usize; 3];
+        let mut encoder = VarIntEncoder::<u64>::new(&mut buffer);
+        
+        assert_eq!(encoder.write_batch_offsets(&[1, 128, 16384], &mut offsets), Ok(6));
+        assert_eq!(offsets, [0, 1, 3]);
+        
+        // Offsets are buffer positions, not relative to the batch
+        assert_eq!(encoder.write_batch_offsets(&[5, 300], &mut offsets), Ok(3));
+        assert_eq!(&offsets[..2], &[6, 7]);
+        
+        let mut short = [0usize; 1];
+        assert_eq!(
+            encoder.write_batch_offsets(&[1, 2], &mut short),
+            Err(Error::BufferTooSmall { needed: 2, actual: 1 })
+        );
+        assert_eq!(encoder.position(), 9);
+    }
+    
+    #[test]
+    fn test_read_at() {
+        let mut buffer = [0u8; 16];
+        let mut offsets = [0usize; 3];
+        let mut encoder = VarIntEncoder::<u32>::new(&mut buffer);
+        let bytes_written = encoder.write_batch_offsets(&[1, 70000, 5], &mut offsets).unwrap();
+        
+        let packed = &buffer[..bytes_written];
+        assert_eq!(read_at::<u32>(packed, offsets[1]), Ok((70000, 3)));
+        assert_eq!(read_at::<u32>(packed, offsets[2]), Ok((5, 1)));
+        
+        assert_eq!(read_at::<u32>(packed, bytes_written), Err(Error::InputTooShort));
+        assert_eq!(read_at::<u32>(packed, usize::MAX), Err(Error::InputTooShort));
+        // An offset inside a value is not detected as such; it decodes whatever follows
+        assert_eq!(read_at::<u8>(packed, offsets[1] + 1), Err(Error::Overflow { bytes_read: 2 }));
+    }
+    
+    #[test]
+    fn test_overflow_bytes_read() {
+        // A corrupt value followed by a good one
+        let stream = [0xFF, 0xFF, 0x7F, 0x2A];
+        
+        let err = decode::<u16>(&stream).unwrap_err();
+        assert_eq!(err, Error::Overflow { bytes_read: 3 });
+        assert_eq!(err.bytes_read(), Some(3));
+        assert_eq!(err.needed(), None);
+        
+        // Skip the scanned bytes to resynchronize
+        assert_eq!(decode::<u16>(&stream[3..]), Ok((42, 1)));
+        
+        assert_eq!(Error::InputTooShort.bytes_read(), None);
+    }
+    
+    #[test]
+    fn test_decode_narrowing() {
+        let mut buffer = [0u8; 10];
+        
+        let bytes_written = encode(1u64 << 40, &mut buffer).unwrap();
+        assert_eq!(
+            decode_narrowing::<u64, u32>(&buffer[..bytes_written]),
+            Err(Error::Overflow { bytes_read: bytes_written })
+        );
+        
+        let bytes_written = encode(300u64, &mut buffer).unwrap();
+        assert_eq!(decode_narrowing::<u64, u32>(&buffer[..bytes_written]), Ok((300u32, 2)));
+        assert_eq!(decode_narrowing::<u64, u8>(&buffer[..bytes_written]), Err(Error::Overflow { bytes_read: 2 }));
+        
+        // Sign changes are checked too
+        let bytes_written = encode(-1i64, &mut buffer).unwrap();
+        assert!(decode_narrowing::<i64, u32>(&buffer[..bytes_written]).is_err());
+        assert_eq!(decode_narrowing::<i64, i8>(&buffer[..bytes_written]), Ok((-1i8, 10)));
+    }
+    
+    #[test]
+    fn test_zigzag_transform_values() {
+        assert_eq!(zigzag_encode_value(-1i32), 1u32);
+        assert_eq!(zigzag_encode_value(1i32), 2u32);
+        assert_eq!(zigzag_encode_value(i32::MAX), u32::MAX - 1);
+        assert_eq!(zigzag_encode_value(i32::MIN), u32::MAX);
+        
+        for value in [0i32, -1, 1, i32::MIN, i32::MAX, i32::MIN + 1, i32::MAX - 1] {
+            assert_eq!(zigzag_decode_value::<i32>(zigzag_encode_value(value)), value);
+        }
+        assert_eq!(zigzag_decode_value::<i64>(u64::MAX), i64::MIN);
+    }
+    
+    #[test]
+    fn test_encoder_failed_write_is_atomic() {
+        let mut buffer = [0xAAu8; 4];
+        let mut encoder = VarIntEncoder::<u64>::new(&mut buffer);
+        assert_eq!(encoder.write(300), Ok(2));
+        
+        // 16384 needs 3 bytes but only 2 remain
+        assert_eq!(encoder.write(16384), Err(Error::BufferTooSmall { needed: 3, actual: 2 }));
+        assert_eq!(encoder.position(), 2);
+        assert_eq!(encoder.remaining_mut(), &[0xAA, 0xAA]);
+        
+        assert_eq!(encoder.write(5), Ok(1));
+        assert_eq!(encoder.write(5), Ok(1));
+        assert_eq!(encoder.write(5), Err(Error::BufferTooSmall { needed: 1, actual: 0 }));
+        assert_eq!(encoder.position(), 4);
+    }
+    
+    #[test]
+    fn test_encoder_failed_write_zigzag_is_atomic() {
+        let mut buffer = [0xAAu8; 3];
+        let mut encoder = VarIntEncoder::<u64>::new(&mut buffer);
+        assert_eq!(encoder.write_zigzag(-1i64), Ok(1));
+        
+        // -8193 zigzags to 16385, which needs 3 bytes but only 2 remain
+        assert_eq!(encoder.write_zigzag(-8193i64), Err(Error::BufferTooSmall { needed: 3, actual: 2 }));
+        assert_eq!(encoder.position(), 1);
+        assert_eq!(encoder.remaining_mut(), &[0xAA, 0xAA]);
+        
+        assert_eq!(encoder.write_zigzag(-64i64), Ok(1));
+        assert_eq!(encoder.position(), 2);
+    }
+    
+    #[test]
+    fn test_encoder_finish() {
+        let values = [1u64, 300, 70000, u64::MAX];
+        
+        let mut expected = [0u8; 32];
+        let expected_len = encode_batch(&values, &mut expected).unwrap();
+        
+        let mut buffer = [0u8; 32];
+        let mut encoder = VarIntEncoder::<u64>::new(&mut buffer);
+        encoder.write_batch(&values).unwrap();
+        let (buf, len) = encoder.finish();
+        
+        assert_eq!(len, expected_len);
+        assert_eq!(buf.len(), 32);
+        assert_eq!(&buf[..len], &expected[..expected_len]);
+        
+        // The buffer is usable again once the encoder is gone
+        buf[len] = 0xFF;
+        assert_eq!(buffer[expected_len], 0xFF);
+    }
+    
+    #[test]
+    fn test_malformed_input_overflows_cleanly() {
+        // Must be a clean error, not a shift-overflow panic in debug builds
+        assert_eq!(decode::<u8>(&[0xFF; 5]), Err(Error::Overflow { bytes_read: 2 }));
+        assert_eq!(decode::<u8>(&[0x80; 5]), Err(Error::Overflow { bytes_read: 2 }));
+        assert_eq!(decode_from_iter::<u8, _>(&mut [0xFF; 5].into_iter()), Err(Error::Overflow { bytes_read: 2 }));
+        
+        let long = [0xFFu8; 32];
+        assert!(decode::<u16>(&long).is_err());
+        assert!(decode::<u64>(&long).is_err());
+        assert!(decode::<u128>(&long).is_err());
+        assert!(decode::<i32>(&long).is_err());
+        
+        // The helpers are safe on their own past the type width
+        assert_eq!(u8::from_byte(0x7F, 2), 0);
+        assert_eq!(u32::from_byte(0x7F, 10), 0);
+        assert_eq!(u8::from_byte(0x7F, 1), 0x80);
+        assert!(!byte_fits::<u8>(0x01, 3));
+        assert!(byte_fits::<u8>(0x80, 3));
+    }
+    
+    #[test]
+    fn test_validate_stream() {
+        let mut buffer = [0u8; 16];
+        let bytes_written = encode_batch(&[1, 300, 70000], &mut buffer).unwrap();
+        
+        assert_eq!(validate_stream::<u32>(&buffer[..bytes_written]), Ok(3));
+        assert_eq!(validate_stream::<u32>(&[]), Ok(0));
+        
+        // Trailing truncated varint
+        assert_eq!(validate_stream::<u32>(&buffer[..bytes_written - 1]), Err(Error::UnexpectedEof));
+        
+        // Complete but too wide for the type, which count_varints cannot see
+        assert_eq!(count_varints(&buffer[..bytes_written]), Ok(3));
+        assert_eq!(validate_stream::<u16>(&buffer[..bytes_written]), Err(Error::Overflow { bytes_read: 3 }));
+    }
+    
+    #[test]
+    fn test_endianness() {
+        let mut little = [0u8; 19];
+        let mut big = [0u8; 19];
+        
+        // 300 = 0b10_0101100: groups 0x2C then 0x02
+        assert_eq!(encode_with_endian(300u32, &mut little, Endianness::Little), Ok(2));
+        assert_eq!(encode_with_endian(300u32, &mut big, Endianness::Big), Ok(2));
+        assert_eq!(&little[..2], &[0xAC, 0x02]);
+        assert_eq!(&big[..2], &[0x82, 0x2C]);
+        
+        assert_eq!(decode_with_endian::<u32>(&little, Endianness::Little), Ok((300, 2)));
+        assert_eq!(decode_with_endian::<u32>(&big, Endianness::Big), Ok((300, 2)));
+        assert_eq!(Endianness::default(), Endianness::Little);
+        
+        for value in [0u128, 1, 127, 128, 16384, u64::MAX as u128, u128::MAX] {
+            let len = encode_with_endian(value, &mut big, Endianness::Big).unwrap();
+            let little_len = encode(value, &mut little).unwrap();
+            assert_eq!(len, little_len);
+            
+            // Same groups in reverse order
+            let reversed: Vec<u8> = big[..len].iter().rev().map(|byte| byte & 0x7F).collect();
+            let groups: Vec<u8> = little[..len].iter().map(|byte| byte & 0x7F).collect();
+            assert_eq!(reversed, groups);
+            
+            assert_eq!(decode_with_endian::<u128>(&big[..len], Endianness::Big), Ok((value, len)));
+        }
+        
+        assert_eq!(decode_with_endian::<u8>(&[], Endianness::Big), Err(Error::InputTooShort));
+        assert_eq!(decode_with_endian::<u8>(&[0x81], Endianness::Big), Err(Error::UnexpectedEof));
+        // 0x82 0x00 is 256, too wide for a u8
+        assert_eq!(decode_with_endian::<u8>(&[0x82, 0x00], Endianness::Big), Err(Error::Overflow { bytes_read: 2 }));
+        assert_eq!(decode_with_endian::<u8>(&[0x81, 0x7F], Endianness::Big), Ok((255, 2)));
+        assert_eq!(decode_with_endian::<u8>(&[0x80, 0x80, 0x01], Endianness::Big), Err(Error::Overflow { bytes_read: 2 }));
+    }
+    
+    #[test]
+    fn test_last_group_capacity_all_decoders() {
+        // The 5th byte of a u32 carries only 4 bits, the 10th of a u64 only 1
+        let u32_max = [0xFF, 0xFF, 0xFF, 0xFF, 0x0F];
+        let u32_bit_32 = [0xFF, 0xFF, 0xFF, 0xFF, 0x10];
+        let u64_max = [0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x01];
+        let u64_bit_64 = [0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x02];
+        
+        assert_eq!(decode::<u32>(&u32_max), Ok((u32::MAX, 5)));
+        assert_eq!(decode::<u32>(&u32_bit_32), Err(Error::Overflow { bytes_read: 5 }));
+        assert_eq!(decode::<u64>(&u64_max), Ok((u64::MAX, 10)));
+        assert_eq!(decode::<u64>(&u64_bit_64), Err(Error::Overflow { bytes_read: 10 }));
+        
+        assert_eq!(decode_from_iter::<u32, _>(&mut u32_max.into_iter()), Ok(u32::MAX));
+        assert_eq!(decode_from_iter::<u32, _>(&mut u32_bit_32.into_iter()), Err(Error::Overflow { bytes_read: 5 }));
+        
+        let mut out = [0u32; 1];
+        assert_eq!(decode_batch_trusted(&u32_max, &mut out), Ok(1));
+        assert_eq!(out[0], u32::MAX);
+        // By design the trusted decoder drops bits past the width instead of checking them
+        assert_eq!(decode_batch_trusted(&u32_bit_32, &mut out), Ok(1));
+        assert_eq!(out[0], 0x0FFF_FFFF);
+        
+        let mut decoder = crate::stream::StreamDecoder::<u32>::new();
+        for &byte in &u32_bit_32[..4] {
+            assert_eq!(decoder.feed(byte), Ok(None));
+        }
+        assert_eq!(decoder.feed(0x10), Err(Error::Overflow { bytes_read: 5 }));
+        
+        // Signed types share the unsigned capacity
+        assert_eq!(decode::<i32>(&u32_max), Ok((-1, 5)));
+        assert_eq!(decode::<i32>(&u32_bit_32), Err(Error::Overflow { bytes_read: 5 }));
+    }
+    
+    
+    #[test]
+    fn test_encoder_write_at() {
+        let mut buffer = [0u8; 256];
+        let mut encoder = VarIntEncoder::<u32>::new(&mut buffer);
+        
+        // Reserve two bytes for the body length, then write the body
+        encoder.write_raw(&[0, 0]).unwrap();
+        for value in 0..100u32 {
+            encoder.write(value * 3).unwrap();
+        }
+        let body_len = (encoder.position() - 2) as u32;
+        assert_eq!(varint_size(body_len), 2);
+        
+        let end = encoder.position();
+        assert_eq!(encoder.write_at(0, 2, body_len), Ok(2));
+        assert_eq!(encoder.position(), end);
+        
+        let (length, prefix_size) = decode::<u32>(encoder.written()).unwrap();
+        assert_eq!((length, prefix_size), (body_len, 2));
+        
+        // The patch must stay within the bytes written so far
+        assert_eq!(encoder.write_at(end - 1, 2, 300), Err(Error::BufferTooSmall { needed: end + 1, actual: end }));
+        assert_eq!(encoder.write_at(usize::MAX, 1, 1), Err(Error::BufferTooSmall { needed: usize::MAX, actual: end }));
+    }
+    
+    #[test]
+    fn test_encoder_write_at_reservation() {
+        let mut buffer = [0u8; 16];
+        let mut encoder = VarIntEncoder::<u32>::new(&mut buffer);
+        encoder.write_raw(&[0, 0]).unwrap();
+        encoder.write(7).unwrap();
+        
+        // A 3-byte value does not fit a 2-byte reservation and the body is untouched
+        assert_eq!(encoder.write_at(0, 2, 16384), Err(Error::BufferTooSmall { needed: 3, actual: 2 }));
+        assert_eq!(encoder.written(), &[0, 0, 7]);
+        
+        // A 1-byte value is padded to the full reservation, leaving no stray varint
+        assert_eq!(encoder.write_at(0, 2, 5), Ok(2));
+        assert_eq!(encoder.written(), &[0x85, 0x00, 7]);
+        let mut decoder = VarIntDecoder::<u32>::new(encoder.written());
+        assert_eq!(decoder.read(), Ok(5));
+        assert_eq!(decoder.read(), Ok(7));
+        assert_eq!(decoder.read(), Err(Error::InputTooShort));
+        
+        // Padding past the type's longest encoding could not be decoded
+        let mut wide = [0u8; 8];
+        let mut encoder = VarIntEncoder::<u8>::new(&mut wide);
+        encoder.write_raw(&[0, 0, 0]).unwrap();
+        assert_eq!(encoder.write_at(0, 3, 1), Err(Error::ValueTooLarge { size: 3, max_len: 2 }));
+    }
+    
+    
+    #[test]
+    fn test_size_histogram() {
+        let histogram = size_histogram(&[0u64, 127, 128, 16384]);
+        assert_eq!(histogram[1], 2);
+        assert_eq!(histogram[2], 1);
+        assert_eq!(histogram[3], 1);
+        assert_eq!(histogram.iter().sum::<usize>(), 4);
+        
+        // The widest encoding lands in the last slot
+        let histogram = size_histogram(&[u128::MAX, 1]);
+        assert_eq!(histogram[19], 1);
+        assert_eq!(histogram[1], 1);
+        assert_eq!(histogram[0], 0);
+        
+        assert_eq!(size_histogram::<u32>(&[]), [0; 20]);
+    }
+    
+    
+    #[test]
+    fn test_decode_recoverable() {
+        let mut buffer = [0u8; 16];
+        let bytes_written = encode_batch(&[1, 300, 70000, 1 << 20], &mut buffer).unwrap();
+        assert_eq!(bytes_written, 9);
+        
+        // The fourth value starts at offset 6 and is cut off
+        let mut out = [0u64; 8];
+        let (count, error) = decode_recoverable(&buffer[..bytes_written - 1], &mut out);
+        assert_eq!(count, 3);
+        assert_eq!(&out[..3], &[1, 300, 70000]);
+        assert_eq!(error, Some((Error::UnexpectedEof, 6)));
+        
+        assert_eq!(decode_recoverable(&buffer[..bytes_written], &mut out), (4, None));
+        assert_eq!(decode_recoverable::<u64>(&[], &mut out), (0, None));
+        
+        // Value too wide for the type
+        let mut narrow = [0u8; 4];
+        assert_eq!(
+            decode_recoverable(&buffer[..bytes_written], &mut narrow),
+            (1, Some((Error::Overflow { bytes_read: 2 }, 1)))
+        );
+        
+        // Output fills up first
+        let mut small = [0u64; 2];
+        assert_eq!(
+            decode_recoverable(&buffer[..bytes_written], &mut small),
+            (2, Some((Error::BufferTooSmall { needed: 4, actual: 2 }, 3)))
+        );
+    }
+    
+    
+    #[test]
+    fn test_default_encoder_decoder() {
+        let mut decoder = VarIntDecoder::<u32>::default();
+        assert_eq!(decoder.read(), Err(Error::InputTooShort));
+        assert_eq!(decoder.position(), 0);
+        
+        let mut encoder = VarIntEncoder::<u32>::default();
+        assert_eq!(encoder.remaining(), 0);
+        assert!(encoder.write(1).is_err());
+        
+        // A placeholder can be swapped for real storage later
+        let mut buffer = [0u8; 4];
+        encoder.reset_with(&mut buffer);
+        assert_eq!(encoder.write(300), Ok(2));
+    }
+    
+    
+    #[test]
+    fn test_decode_saturating() {
+        let mut buffer = [0u8; 10];
+        
+        // 300 does not fit in a u8, so it clamps but consumes the whole value
+        let bytes_written = encode(300u32, &mut buffer).unwrap();
+        assert_eq!(decode_saturating::<u8>(&buffer[..bytes_written]), Ok((255u8, 2)));
+        assert_eq!(decode::<u8>(&buffer[..bytes_written]), Err(Error::Overflow { bytes_read: 2 }));
+        
+        let bytes_written = encode(u64::MAX, &mut buffer).unwrap();
+        assert_eq!(decode_saturating::<u32>(&buffer[..bytes_written]), Ok((u32::MAX, 10)));
+        
+        // In-range values pass through unchanged
+        assert_eq!(decode_saturating::<u8>(&[0x2A, 0x01]), Ok((42u8, 1)));
+        assert_eq!(decode_saturating::<u16>(&[0xAC, 0x02]), Ok((300u16, 2)));
+        
+        // Non-zero wrappers clamp through their unsigned type too
+        let bytes_written = encode(300u32, &mut buffer).unwrap();
+        assert_eq!(
+            decode_saturating::<core::num::NonZeroU8>(&buffer[..bytes_written]),
+            Ok((core::num::NonZeroU8::MAX, 2))
+        );
+        
+        // Missing input is still an error
+        assert_eq!(decode_saturating::<u8>(&[]), Err(Error::InputTooShort));
+        assert_eq!(decode_saturating::<u8>(&[0xFF, 0xFF, 0xFF]), Err(Error::UnexpectedEof));
+        assert_eq!(decode_saturating::<u32>(&[0x80]), Err(Error::UnexpectedEof));
+    }
+    
+    
+    #[test]
+    fn test_zigzag_size() {
+        // Plain varint sizing sees the full two's complement width
+        assert_eq!(varint_size(-1i32), 5);
+        assert_eq!(zigzag_size(-1i32), 1);
+        
+        let mut buffer = [0u8; 19];
+        for value in [0i64, -1, 63, -64, 64, -65, i64::MAX, i64::MIN] {
+            assert_eq!(zigzag_size(value), encode_zigzag(value, &mut buffer).unwrap());
+        }
+        assert_eq!(zigzag_size(i128::MIN), 19);
+    }
+    
+    
+    #[test]
+    fn test_encode_u64_const() {
+        const ENCODED: ([u8; 10], usize) = encode_u64_const(300);
+        const _: () = assert!(ENCODED.1 == 2 && ENCODED.0[0] == 0xAC && ENCODED.0[1] == 0x02);
+        
+        static TABLE: [([u8; 10], usize); 3] = [encode_u64_const(0), encode_u64_const(127), encode_u64_const(u64::MAX)];
+        assert_eq!(&TABLE[0].0[..TABLE[0].1], &[0x00]);
+        assert_eq!(&TABLE[1].0[..TABLE[1].1], &[0x7F]);
+        
+        // Matches the runtime encoder
+        for value in [1u64, 128, 16384, 1 << 35, u64::MAX] {
+            let (bytes, len) = encode_u64_const(value);
+            let (expected, expected_len) = encode_array(value);
+            assert_eq!(&bytes[..len], &expected[..expected_len]);
+        }
+        assert_eq!(TABLE[2].1, 10);
+    }
+    
+    
+    #[test]
+    fn test_decoder_read_zigzag_signed() {
+        let mut buffer = [0u8; 16];
+        let mut pos = encode_zigzag(-100i32, &mut buffer).unwrap();
+        pos += encode_zigzag(i32::MAX, &mut buffer[pos..]).unwrap();
+        
+        let mut decoder = VarIntDecoder::<u32>::new(&buffer[..pos]);
+        let value: i32 = decoder.read_zigzag_signed().unwrap();
+        assert_eq!(value, -100);
+        assert_eq!(decoder.read_zigzag_signed(), Ok(i32::MAX));
+        assert_eq!(decoder.read_zigzag_signed(), Err(Error::InputTooShort));
+        
+        let mut decoder = VarIntDecoder::<u8>::new(&[0x03]);
+        assert_eq!(decoder.read_zigzag_signed(), Ok(-2i8));
+    }
+    
+    
+    #[test]
+    fn test_varint_signed_sibling() {
+        fn round<T: VarInt + PartialEq + core::fmt::Debug>(value: T) {
+            assert_eq!(T::from_signed(value.to_signed()), value);
+        }
+        
+        round(0u8);
+        round(u32::MAX);
+        round(u128::MAX);
+        round(usize::MAX);
+        round(-5i64);
+        round(core::num::NonZeroU32::new(u32::MAX).unwrap());
+        round(core::num::NonZeroI16::new(-1).unwrap());
+        
+        // Two's complement reinterpretation, including the 128-bit pair
+        assert_eq!(u32::MAX.to_signed(), -1i32);
+        assert_eq!(u128::from_signed(i128::MIN), 1u128 << 127);
+        assert_eq!(core::num::NonZeroU8::new(0xFF).unwrap().to_signed().get(), -1i8);
+        assert_eq!((-7i16).to_signed(), -7i16);
+    }
+    
+    
+    #[test]
+    fn test_canonical_invalid_offset() {
+        // The offset points at the first redundant byte
+        assert_eq!(decode_canonical::<u64>(&[0xAC, 0x82, 0x00]).unwrap_err().offset(), Some(2));
+        assert_eq!(decode_canonical::<u64>(&[0x80, 0x80, 0x80, 0x00]).unwrap_err().offset(), Some(1));
+    }
+    
+    
+    #[test]
+    fn test_fold_and_sum_values() {
+        let mut buffer = [0u8; 16];
+        let bytes_written = encode_batch(&[1, 2, 3, 4], &mut buffer).unwrap();
+        assert_eq!(sum_values::<u64>(&buffer[..bytes_written]), Ok(10));
+        assert_eq!(sum_values::<u64>(&[]), Ok(0));
+        
+        let max = fold_values(&buffer[..bytes_written], 0u64, |acc, value: u64| acc.max(value));
+        assert_eq!(max, Ok(4));
+        
+        // Signed types decode through the plain two's complement encoding
+        let bytes_written = encode(-3i32, &mut buffer).unwrap();
+        assert_eq!(sum_values::<i32>(&buffer[..bytes_written]), Ok(-3));
+        
+        // The first error stops the fold
+        assert_eq!(sum_values::<u64>(&[0x01, 0x80]), Err(Error::UnexpectedEof));
+        assert_eq!(sum_values::<u8>(&[0x01, 0xAC, 0x02]), Err(Error::Overflow { bytes_read: 2 }));
+    }
+    
+    
+    #[test]
+    fn test_encoder_write_batch_partial() {
+        // 1 + 2 + 3 + 1 bytes fit in 7, the next two-byte value does not
+        let values = [1u32, 300, 70000, 5, 200, 6];
+        let mut buffer = [0u8; 8];
+        let mut encoder = VarIntEncoder::<u32>::new(&mut buffer);
+        
+        assert_eq!(encoder.write_batch_partial(&values), (4, 7));
+        assert_eq!(encoder.position(), 7);
+        
+        // The rest goes into the next packet, starting with the value that did not fit
+        let mut next = [0u8; 8];
+        let mut encoder = VarIntEncoder::<u32>::new(&mut next);
+        assert_eq!(encoder.write_batch_partial(&values[4..]), (2, 3));
+        
+        let mut decoded = [0u32; 6];
+        assert_eq!(decode_batch_trusted(&buffer[..7], &mut decoded[..4]), Ok(4));
+        assert_eq!(decode_batch_trusted(&next[..3], &mut decoded[4..]), Ok(2));
+        assert_eq!(decoded, values);
+        
+        let mut empty: [u8; 0] = [];
+        let mut encoder = VarIntEncoder::<u32>::new(&mut empty);
+        assert_eq!(encoder.write_batch_partial(&values), (0, 0));
+    }
+    
+    
+    #[test]
+    fn test_zigzag_batch_size() {
+        let values = [-1i32, 100, -100000];
+        let needed = zigzag_batch_size(&values);
+        assert_eq!(needed, 1 + 2 + 3);
+        
+        let mut buffer = [0u8; 16];
+        let mut encoder = VarIntEncoder::<u32>::new(&mut buffer);
+        assert_eq!(encoder.write_zigzag_batch(&values), Ok(needed));
+        assert_eq!(encode_zigzag_batch(&values, &mut buffer), Ok(needed));
+        
+        assert_eq!(zigzag_batch_size::<i64>(&[]), 0);
+    }
+    
+    
+    #[test]
+    fn test_decode_after_sentinel() {
+        assert_eq!(decode_after_sentinel::<u32>(&[0x00, 0x00, 0xAC, 0x02], 0x00), Ok((300, 4)));
+        // No sentinel is fine too
+        assert_eq!(decode_after_sentinel::<u32>(&[0xAC, 0x02, 0x00], 0x00), Ok((300, 2)));
+        assert_eq!(decode_after_sentinel::<u8>(&[0x7E, 0x05], 0x7E), Ok((5, 2)));
+        
+        assert_eq!(decode_after_sentinel::<u32>(&[0x00, 0x00], 0x00), Err(Error::InputTooShort));
+        assert_eq!(decode_after_sentinel::<u32>(&[], 0x00), Err(Error::InputTooShort));
+        assert_eq!(decode_after_sentinel::<u32>(&[0x00, 0x80], 0x00), Err(Error::UnexpectedEof));
+    }
+    
+    
+    #[test]
+    fn test_error_ord_and_hash() {
+        use self::std::collections::{BTreeMap, HashSet};
+        
+        let errors = [
+            Error::UnexpectedEof,
+            Error::BufferTooSmall { needed: 4, actual: 2 },
+            Error::Overflow { bytes_read: 3 },
+            Error::UnexpectedEof,
+            Error::BufferTooSmall { needed: 2, actual: 1 },
+            Error::InputTooShort,
+        ];
+        
+        let mut counts = BTreeMap::new();
+        for error in errors {
+            *counts.entry(error).or_insert(0usize) += 1;
+        }
+        
+        // Declaration order of the variants, then field order
+        let ordered: Vec<_> = counts.into_iter().collect();
+        assert_eq!(ordered, [
+            (Error::BufferTooSmall { needed: 2, actual: 1 }, 1),
+            (Error::BufferTooSmall { needed: 4, actual: 2 }, 1),
+            (Error::Overflow { bytes_read: 3 }, 1),
+            (Error::InputTooShort, 1),
+            (Error::UnexpectedEof, 2),
+        ]);
+        
+        let distinct: HashSet<Error> = errors.into_iter().collect();
+        assert_eq!(distinct.len(), 5);
+    }
+    
+    
+    #[test]
+    fn test_indexed_values_from() {
+        let mut buffer = [0u8; 16];
+        let bytes_written = encode_batch(&[1, 128, 16384], &mut buffer).unwrap();
+        
+        let items: Vec<_> = indexed_values_from::<u64>(&buffer[..bytes_written]).collect();
+        assert_eq!(items, [Ok((1, 0..1)), Ok((128, 1..3)), Ok((16384, 3..6))]);
+        
+        // Ranges slice back to the exact encoding of each value
+        for item in &items {
+            let (value, range) = item.clone().unwrap();
+            assert_eq!(decode::<u64>(&buffer[range.clone()]), Ok((value, range.len())));
+        }
+        
+        // Stops after the first error
+        let mut iter = indexed_values_from::<u8>(&[0x05, 0xAC, 0x02, 0x01]);
+        assert_eq!(iter.next(), Some(Ok((5, 0..1))));
+        assert_eq!(iter.next(), Some(Err(Error::Overflow { bytes_read: 2 })));
+        assert_eq!(iter.next(), None);
+    }
+    
+    
+    #[test]
+    fn test_encode_at() {
+        let mut buffer = [0u8; 8];
+        
+        let first = encode_at(300u32, &mut buffer, 0).unwrap();
+        let second = encode_at(70000u64, &mut buffer, first).unwrap();
+        assert_eq!((first, second), (2, 3));
+        
+        let (value, bytes_read) = decode::<u32>(&buffer).unwrap();
+        assert_eq!((value, bytes_read), (300, first));
+        assert_eq!(decode::<u64>(&buffer[first..]), Ok((70000, second)));
+        
+        // Bounds are checked against the whole buffer, and nothing is written on failure
+        assert_eq!(encode_at(70000u64, &mut buffer, 6), Err(Error::BufferTooSmall { needed: 9, actual: 8 }));
+        assert_eq!(encode_at(1u8, &mut buffer, 9), Err(Error::BufferTooSmall { needed: 10, actual: 8 }));
+        assert_eq!(&buffer[5..], &[0, 0, 0]);
+        assert_eq!(encode_at(1u8, &mut buffer, 7), Ok(1));
+    }
+    
+    
+    #[test]
+    fn test_decode_until() {
+        // 1, 300 and a value with 0xFF inside it, then the delimiter
+        let mut buf = [0u8; 16];
+        let mut pos = 0;
+        for value in [1u32, 300, 0x7F_FF80] {
+            pos += encode(value, &mut buf[pos..]).unwrap();
+        }
+        buf[pos] = 0xFF;
+        buf[pos + 1] = 0x05;
+        
+        let mut out = [0u32; 4];
+        assert_eq!(decode_until(&buf[..pos + 2], 0xFF, &mut out), Ok((3, pos + 1)));
+        assert_eq!(out[..3], [1, 300, 0x7F_FF80]);
+        
+        // An immediate delimiter is an empty region
+        assert_eq!(decode_until::<u32>(&[0xFF], 0xFF, &mut out), Ok((0, 1)));
+        
+        // No delimiter before the end of the buffer
+        assert_eq!(decode_until::<u32>(&buf[..pos], 0xFF, &mut out), Err(Error::UnexpectedEof));
+        
+        let mut small = [0u32; 2];
+        assert_eq!(
+            decode_until(&buf[..pos + 1], 0xFF, &mut small),
+            Err(Error::BufferTooSmall { needed: 3, actual: 2 })
+        );
+    }
+    
+    
+    #[test]
+    fn test_zigzag_values_fixed_count() {
+        let mut buf = [0u8; 16];
+        let bytes_written = encode_zigzag_batch(&[-1i32, 1, -100, 7], &mut buf).unwrap();
+        assert_eq!(&buf[..4], &[0x01, 0x02, 0xC7, 0x01]);
+        
+        // Read only the first three values of the stream
+        let mut decoded = [0i32; 3];
+        for (slot, value) in decoded.iter_mut().zip(zigzag_values_from::<i32>(&buf[..bytes_written]).take(3)) {
+            *slot = value.unwrap();
+        }
+        assert_eq!(decoded, [-1, 1, -100]);
+        
+        let sum: Result<i32, Error> = zigzag_values_from::<i32>(&buf[..bytes_written]).sum();
+        assert_eq!(sum, Ok(-93));
+    }
+    
+    #[test]
+    fn test_max_varint_len() {
+        // Checked at compile time against the widest types
+        const _: () = assert!(MAX_VARINT_LEN == <u128 as VarInt>::MAX_ENCODED_LEN);
+        const _: () = assert!(MAX_VARINT_LEN == <i128 as VarInt>::MAX_ENCODED_LEN);
+        
+        assert_eq!(encode(u128::MAX, &mut [0u8; MAX_VARINT_LEN]), Ok(MAX_VARINT_LEN));
+        assert_eq!(encode_zigzag(i128::MIN, &mut [0u8; MAX_VARINT_LEN]), Ok(MAX_VARINT_LEN));
+        assert_eq!(encode_array(u128::MAX).1, MAX_VARINT_LEN);
+    }
+}
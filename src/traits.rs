@@ -1,14 +1,50 @@
+use core::num::{
+    NonZeroU8, NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU128, NonZeroUsize,
+    NonZeroI8, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI128, NonZeroIsize,
+};
+use crate::error::Error;
+use crate::zigzag::ZigZag;
+
 /// Generic trait for variable-length integer encoding
 pub trait VarInt: Copy + Sized {
     /// The corresponding unsigned type used for internal encoding operations
     type Unsigned: Copy + VarIntOps;
     
+    /// The signed type of the same width, whose zigzag encoding is carried in
+    /// unsigned types (`u32 -> i32`); signed types map to themselves
+    type Signed: ZigZag;
+    
+    /// Maximum number of bytes an encoded value of this type can occupy
+    const MAX_ENCODED_LEN: usize;
+    
     /// Convert to the corresponding unsigned type
     fn to_unsigned(self) -> Self::Unsigned;
     
     /// Convert from the corresponding unsigned type
+    ///
+    /// # Panics
+    /// Panics if `Self` cannot represent `value`, such as zero for the `NonZero`
+    /// types. Every decoder in this crate goes through `try_from_unsigned`
+    /// instead; call that for values read from untrusted input.
     fn from_unsigned(value: Self::Unsigned) -> Self;
     
+    /// Reinterpret as the signed type of the same width (two's complement bit cast)
+    ///
+    /// Signed types return themselves, so `from_signed(x.to_signed()) == x` for every type
+    fn to_signed(self) -> Self::Signed;
+    
+    /// Reinterpret from the signed type of the same width, the inverse of `to_signed`
+    fn from_signed(value: Self::Signed) -> Self;
+    
+    /// Convert from the corresponding unsigned type, rejecting values `Self` cannot represent
+    ///
+    /// The decoders use this, so types with invalid bit patterns (such as zero for
+    /// `NonZeroU32`) report `Error::InvalidEncoding` instead of panicking
+    #[inline]
+    fn try_from_unsigned(value: Self::Unsigned) -> Result<Self, Error> {
+        Ok(Self::from_unsigned(value))
+    }
+    
     /// Determine how many bytes are needed to encode this value
     fn varint_size(self) -> usize;
 }
@@ -18,6 +54,9 @@ pub trait VarIntOps: Copy + Sized {
     /// Maximum number of bits for this type
     const BITS: usize;
     
+    /// Largest value of this type
+    const MAX: Self;
+    
     /// Check if another byte is needed for encoding (value >= 0x80)
     fn needs_another_byte(self) -> bool;
     
@@ -30,7 +69,10 @@ pub trait VarIntOps: Copy + Sized {
     /// Shift right by 7 bits
     fn shift_right_7(self) -> Self;
     
-    /// Build a value from a byte
+    /// Build a value from the 7 payload bits of a byte placed at group `shift`
+    ///
+    /// Groups at or beyond the type width contribute nothing, rather than
+    /// overflowing the shift
     fn from_byte(byte: u8, shift: usize) -> Self;
     
     /// Get the number of leading zeros
@@ -46,6 +88,8 @@ macro_rules! impl_varint_ops {
         impl VarIntOps for $type {
             const BITS: usize = $bits;
             
+            const MAX: Self = <$type>::MAX;
+            
             #[inline]
             fn needs_another_byte(self) -> bool {
                 self >= 0x80
@@ -68,7 +112,7 @@ macro_rules! impl_varint_ops {
             
             #[inline]
             fn from_byte(byte: u8, shift: usize) -> Self {
-                ((byte & 0x7F) as Self) << ((shift * 7) as u32)
+                ((byte & 0x7F) as Self).checked_shl((shift * 7) as u32).unwrap_or(0)
             }
             
             #[inline]
@@ -86,9 +130,12 @@ macro_rules! impl_varint_ops {
 
 // Implement VarInt for unsigned types
 macro_rules! impl_unsigned_varint {
-    ($type:ty, $bits:expr) => {
+    ($type:ty, $bits:expr, $signed:ty) => {
         impl VarInt for $type {
             type Unsigned = Self;
+            type Signed = $signed;
+            
+            const MAX_ENCODED_LEN: usize = ($bits as usize).div_ceil(7);
             
             #[inline]
             fn to_unsigned(self) -> Self::Unsigned {
@@ -100,13 +147,23 @@ macro_rules! impl_unsigned_varint {
                 value
             }
             
+            #[inline]
+            fn to_signed(self) -> Self::Signed {
+                self as $signed
+            }
+            
+            #[inline]
+            fn from_signed(value: Self::Signed) -> Self {
+                value as Self
+            }
+            
             #[inline]
             fn varint_size(self) -> usize {
                 if self == 0 {
                     return 1;
                 }
                 let bits_needed = Self::Unsigned::BITS - self.leading_zeros();
-                ((bits_needed + 6) / 7) as usize // 7 bits per byte, round up
+                bits_needed.div_ceil(7) as usize // 7 bits per byte, round up
             }
         }
     };
@@ -117,6 +174,9 @@ macro_rules! impl_signed_varint {
     ($type:ty, $unsigned:ty) => {
         impl VarInt for $type {
             type Unsigned = $unsigned;
+            type Signed = Self;
+            
+            const MAX_ENCODED_LEN: usize = <$unsigned as VarIntOps>::BITS.div_ceil(7);
             
             #[inline]
             fn to_unsigned(self) -> Self::Unsigned {
@@ -128,6 +188,16 @@ macro_rules! impl_signed_varint {
                 value as Self
             }
             
+            #[inline]
+            fn to_signed(self) -> Self::Signed {
+                self
+            }
+            
+            #[inline]
+            fn from_signed(value: Self::Signed) -> Self {
+                value
+            }
+            
             #[inline]
             fn varint_size(self) -> usize {
                 // For signed types, calculate size based on actual bit pattern
@@ -137,7 +207,7 @@ macro_rules! impl_signed_varint {
                     return 1;
                 }
                 let bits_needed = Self::Unsigned::BITS - value.leading_zeros();
-                ((bits_needed + 6) / 7) as usize
+                bits_needed.div_ceil(7) as usize
             }
         }
     };
@@ -149,15 +219,78 @@ impl_varint_ops!(u16, 16);
 impl_varint_ops!(u32, 32);
 impl_varint_ops!(u64, 64);
 impl_varint_ops!(u128, 128);
+// Pointer-sized integers use the platform width, so their encoding is target-dependent
+impl_varint_ops!(usize, usize::BITS as usize);
 
-impl_unsigned_varint!(u8, 8);
-impl_unsigned_varint!(u16, 16);
-impl_unsigned_varint!(u32, 32);
-impl_unsigned_varint!(u64, 64);
-impl_unsigned_varint!(u128, 128);
+impl_unsigned_varint!(u8, 8, i8);
+impl_unsigned_varint!(u16, 16, i16);
+impl_unsigned_varint!(u32, 32, i32);
+impl_unsigned_varint!(u64, 64, i64);
+impl_unsigned_varint!(u128, 128, i128);
+impl_unsigned_varint!(usize, usize::BITS, isize);
 
 impl_signed_varint!(i8, u8);
 impl_signed_varint!(i16, u16);
 impl_signed_varint!(i32, u32);
 impl_signed_varint!(i64, u64);
-impl_signed_varint!(i128, u128); 
\ No newline at end of file
+impl_signed_varint!(i128, u128);
+impl_signed_varint!(isize, usize);
+
+// Implement VarInt for non-zero types by delegating to the inner integer
+macro_rules! impl_nonzero_varint {
+    ($type:ty, $inner:ty, $signed:ty) => {
+        impl VarInt for $type {
+            type Unsigned = <$inner as VarInt>::Unsigned;
+            type Signed = $signed;
+            
+            const MAX_ENCODED_LEN: usize = <$inner as VarInt>::MAX_ENCODED_LEN;
+            
+            #[inline]
+            fn to_unsigned(self) -> Self::Unsigned {
+                self.get().to_unsigned()
+            }
+            
+            /// # Panics
+            /// Panics if `value` is zero; use `try_from_unsigned` for untrusted input
+            #[inline]
+            fn from_unsigned(value: Self::Unsigned) -> Self {
+                Self::new(<$inner>::from_unsigned(value)).expect("zero is not a valid non-zero value")
+            }
+            
+            #[inline]
+            fn try_from_unsigned(value: Self::Unsigned) -> Result<Self, Error> {
+                Self::new(<$inner>::from_unsigned(value)).ok_or_else(|| Error::invalid_encoding(0))
+            }
+            
+            // The bit cast keeps the value non-zero, so these cannot fail
+            #[inline]
+            fn to_signed(self) -> Self::Signed {
+                <Self::Signed as VarInt>::try_from_unsigned(self.to_unsigned()).expect("non-zero bit cast is non-zero")
+            }
+            
+            #[inline]
+            fn from_signed(value: Self::Signed) -> Self {
+                Self::from_unsigned(value.to_unsigned())
+            }
+            
+            #[inline]
+            fn varint_size(self) -> usize {
+                self.get().varint_size()
+            }
+        }
+    };
+}
+
+impl_nonzero_varint!(NonZeroU8, u8, NonZeroI8);
+impl_nonzero_varint!(NonZeroU16, u16, NonZeroI16);
+impl_nonzero_varint!(NonZeroU32, u32, NonZeroI32);
+impl_nonzero_varint!(NonZeroU64, u64, NonZeroI64);
+impl_nonzero_varint!(NonZeroU128, u128, NonZeroI128);
+impl_nonzero_varint!(NonZeroUsize, usize, NonZeroIsize);
+
+impl_nonzero_varint!(NonZeroI8, i8, Self);
+impl_nonzero_varint!(NonZeroI16, i16, Self);
+impl_nonzero_varint!(NonZeroI32, i32, Self);
+impl_nonzero_varint!(NonZeroI64, i64, Self);
+impl_nonzero_varint!(NonZeroI128, i128, Self);
+impl_nonzero_varint!(NonZeroIsize, isize, Self);
@@ -0,0 +1,202 @@
+use crate::error::Error;
+use crate::encoding::{encode, decode, varint_size};
+use crate::zigzag::{ZigZag, encode_zigzag, decode_zigzag};
+
+/// Trait for integers that can appear as a member of a `VarIntTuple`
+///
+/// Unsigned members are written as plain varints and signed members go
+/// through zigzag encoding, so small negative values stay small.
+pub trait TupleField: Copy {
+    /// Returns the number of bytes needed to encode this member
+    fn field_size(self) -> usize;
+    
+    /// Encodes this member, returning the number of bytes written
+    fn encode_field(self, buf: &mut [u8]) -> Result<usize, Error>;
+    
+    /// Decodes a member, returning it and the number of bytes read
+    fn decode_field(buf: &[u8]) -> Result<(Self, usize), Error>;
+}
+
+// Implement TupleField for unsigned types with plain varint encoding
+macro_rules! impl_unsigned_field {
+    ($($type:ty),*) => {
+        $(
+            impl TupleField for $type {
+                #[inline]
+                fn field_size(self) -> usize {
+                    varint_size(self)
+                }
+                
+                #[inline]
+                fn encode_field(self, buf: &mut [u8]) -> Result<usize, Error> {
+                    encode(self, buf)
+                }
+                
+                #[inline]
+                fn decode_field(buf: &[u8]) -> Result<(Self, usize), Error> {
+                    decode(buf)
+                }
+            }
+        )*
+    };
+}
+
+// Implement TupleField for signed types with zigzag encoding
+macro_rules! impl_signed_field {
+    ($($type:ty),*) => {
+        $(
+            impl TupleField for $type {
+                #[inline]
+                fn field_size(self) -> usize {
+                    varint_size(self.zigzag_encode())
+                }
+                
+                #[inline]
+                fn encode_field(self, buf: &mut [u8]) -> Result<usize, Error> {
+                    encode_zigzag(self, buf)
+                }
+                
+                #[inline]
+                fn decode_field(buf: &[u8]) -> Result<(Self, usize), Error> {
+                    decode_zigzag(buf)
+                }
+            }
+        )*
+    };
+}
+
+impl_unsigned_field!(u8, u16, u32, u64, u128, usize);
+impl_signed_field!(i8, i16, i32, i64, i128, isize);
+
+/// Trait for tuples of up to six integers encoded as consecutive varints
+pub trait VarIntTuple: Sized {
+    /// Returns the number of bytes needed to encode every member
+    fn tuple_size(&self) -> usize;
+    
+    /// Encodes the members in order, returning the number of bytes written
+    fn encode_members(&self, buf: &mut [u8]) -> Result<usize, Error>;
+    
+    /// Decodes the members in order, returning the tuple and the number of bytes read
+    fn decode_members(buf: &[u8]) -> Result<(Self, usize), Error>;
+}
+
+// Implement VarIntTuple for a tuple of the given member type parameters
+macro_rules! impl_varint_tuple {
+    ($($name:ident),+) => {
+        impl<$($name: TupleField),+> VarIntTuple for ($($name,)+) {
+            #[inline]
+            #[allow(non_snake_case)]
+            fn tuple_size(&self) -> usize {
+                let ($($name,)+) = *self;
+                0 $(+ $name.field_size())+
+            }
+            
+            #[inline]
+            #[allow(non_snake_case)]
+            fn encode_members(&self, buf: &mut [u8]) -> Result<usize, Error> {
+                let ($($name,)+) = *self;
+                let mut pos = 0;
+                $(pos += $name.encode_field(&mut buf[pos..])?;)+
+                Ok(pos)
+            }
+            
+            #[inline]
+            #[allow(non_snake_case)]
+            fn decode_members(buf: &[u8]) -> Result<(Self, usize), Error> {
+                let mut pos = 0;
+                $(
+                    let ($name, bytes_read) = $name::decode_field(&buf[pos..])?;
+                    pos += bytes_read;
+                )+
+                Ok((($($name,)+), pos))
+            }
+        }
+    };
+}
+
+impl_varint_tuple!(A);
+impl_varint_tuple!(A, B);
+impl_varint_tuple!(A, B, C);
+impl_varint_tuple!(A, B, C, D);
+impl_varint_tuple!(A, B, C, D, E);
+impl_varint_tuple!(A, B, C, D, E, F);
+
+/// Encodes a tuple of integers as consecutive varints
+///
+/// Signed members are zigzag encoded. The total size is checked first, so
+/// nothing is written if the buffer is too small.
+///
+/// Returns the number of bytes written
+///
+/// # Parameters
+/// * `tuple` - The tuple to encode, with up to six members
+/// * `buf` - Output buffer
+///
+/// # Errors
+/// Returns `Error::BufferTooSmall` if the buffer cannot hold every member
+pub fn encode_tuple<T: VarIntTuple>(tuple: T, buf: &mut [u8]) -> Result<usize, Error> {
+    let needed_size = tuple.tuple_size();
+    
+    if buf.len() < needed_size {
+        return Err(Error::buffer_too_small(needed_size, buf.len()));
+    }
+    
+    tuple.encode_members(buf)
+}
+
+/// Decodes a tuple of integers written by `encode_tuple`
+///
+/// Returns the decoded tuple and the number of bytes read
+///
+/// # Errors
+/// * Returns `Error::InputTooShort` or `Error::UnexpectedEof` if the input ends before the last member
+/// * Returns `Error::Overflow` if a member does not fit in its type
+pub fn decode_tuple<T: VarIntTuple>(buf: &[u8]) -> Result<(T, usize), Error> {
+    T::decode_members(buf)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_tuple_round_trip() {
+        let mut buf = [0u8; 32];
+        let tuple = (42u32, 1000u16, -5i8);
+        
+        let bytes_written = encode_tuple(tuple, &mut buf).unwrap();
+        assert_eq!(bytes_written, varint_size(42u32) + varint_size(1000u16) + varint_size((-5i8).zigzag_encode()));
+        assert_eq!(bytes_written, 4);
+        assert_eq!(&buf[..bytes_written], &[0x2A, 0xE8, 0x07, 0x09]);
+        
+        assert_eq!(decode_tuple::<(u32, u16, i8)>(&buf[..bytes_written]), Ok((tuple, bytes_written)));
+    }
+    
+    #[test]
+    fn test_tuple_arities() {
+        let mut buf = [0u8; 64];
+        
+        let bytes_written = encode_tuple((7u8,), &mut buf).unwrap();
+        assert_eq!(decode_tuple(&buf[..bytes_written]), Ok(((7u8,), 1)));
+        
+        let tuple = (u64::MAX, -1i64, 0u8, i128::MIN, 300usize, -300isize);
+        let bytes_written = encode_tuple(tuple, &mut buf).unwrap();
+        assert_eq!(bytes_written, tuple.tuple_size());
+        assert_eq!(decode_tuple(&buf[..bytes_written]), Ok((tuple, bytes_written)));
+    }
+    
+    #[test]
+    fn test_tuple_errors() {
+        let mut small = [0u8; 3];
+        assert_eq!(
+            encode_tuple((42u32, 1000u16, -5i8), &mut small),
+            Err(Error::BufferTooSmall { needed: 4, actual: 3 })
+        );
+        assert_eq!(small, [0u8; 3]);
+        
+        // Input ends before the last member
+        assert_eq!(decode_tuple::<(u32, u16, i8)>(&[0x2A, 0xE8, 0x07]), Err(Error::InputTooShort));
+        // Member too wide for its type
+        assert_eq!(decode_tuple::<(u8, u8)>(&[0x2A, 0x80, 0x02]), Err(Error::Overflow { bytes_read: 2 }));
+    }
+}
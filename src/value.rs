@@ -1,8 +1,19 @@
-use crate::{encode, decode, encode_zigzag, decode_zigzag, Error};
+use core::cmp::Ordering;
+use core::fmt;
+use crate::{encode, decode, encode_zigzag, decode_zigzag, encode_leb128_signed, decode_leb128_signed, Error};
 
 /// Enum representing different integer types that can be encoded as varints.
 /// Each variant wraps a specific Rust integer type.
-#[derive(Debug, Clone, Copy, PartialEq)]
+///
+/// Values are ordered by their numeric value across all variants, so
+/// `U8(200) > I8(-5)`. `U128` values above `i128::MAX` compare greater than
+/// every other value. When two values of different variants are numerically
+/// equal, the type identifier byte breaks the tie (unsigned before signed,
+/// narrower before wider), keeping the order consistent with `PartialEq`.
+///
+/// Hashing follows equality: values of different variants are distinct keys
+/// even when numerically equal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum VarintValue {
     /// Unsigned 8-bit integer
     U8(u8),
@@ -26,11 +37,301 @@ pub enum VarintValue {
     I128(i128),
 }
 
+/// Lightweight tag naming one of the `VarintValue` variants, without a value
+///
+/// Used to describe the layout of an untagged stream, see `decode_with_schema`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum VarintTypeTag {
+    /// Unsigned 8-bit integer
+    U8,
+    /// Unsigned 16-bit integer
+    U16,
+    /// Unsigned 32-bit integer
+    U32,
+    /// Unsigned 64-bit integer
+    U64,
+    /// Unsigned 128-bit integer
+    U128,
+    /// Signed 8-bit integer, zigzag encoded
+    I8,
+    /// Signed 16-bit integer, zigzag encoded
+    I16,
+    /// Signed 32-bit integer, zigzag encoded
+    I32,
+    /// Signed 64-bit integer, zigzag encoded
+    I64,
+    /// Signed 128-bit integer, zigzag encoded
+    I128,
+}
+
+impl VarintTypeTag {
+    /// Returns the type identifier byte used by `VarintValue::to_bytes` for this type
+    #[inline]
+    pub const fn get_type_id(self) -> u8 {
+        match self {
+            VarintTypeTag::U8 => TYPE_BITS_UNSIGNED | SIZE_BITS_8,
+            VarintTypeTag::U16 => TYPE_BITS_UNSIGNED | SIZE_BITS_16,
+            VarintTypeTag::U32 => TYPE_BITS_UNSIGNED | SIZE_BITS_32,
+            VarintTypeTag::U64 => TYPE_BITS_UNSIGNED | SIZE_BITS_64,
+            VarintTypeTag::U128 => TYPE_BITS_UNSIGNED | SIZE_BITS_128,
+            VarintTypeTag::I8 => TYPE_BITS_SIGNED | SIZE_BITS_8,
+            VarintTypeTag::I16 => TYPE_BITS_SIGNED | SIZE_BITS_16,
+            VarintTypeTag::I32 => TYPE_BITS_SIGNED | SIZE_BITS_32,
+            VarintTypeTag::I64 => TYPE_BITS_SIGNED | SIZE_BITS_64,
+            VarintTypeTag::I128 => TYPE_BITS_SIGNED | SIZE_BITS_128,
+        }
+    }
+    
+    /// Returns the tag for a type identifier byte, or `None` if the byte is not a valid type
+    #[inline]
+    pub const fn from_type_id(type_id: u8) -> Option<Self> {
+        match (type_id & 0b111_00000, type_id & 0b000_11111) {
+            (TYPE_BITS_UNSIGNED, SIZE_BITS_8) => Some(VarintTypeTag::U8),
+            (TYPE_BITS_UNSIGNED, SIZE_BITS_16) => Some(VarintTypeTag::U16),
+            (TYPE_BITS_UNSIGNED, SIZE_BITS_32) => Some(VarintTypeTag::U32),
+            (TYPE_BITS_UNSIGNED, SIZE_BITS_64) => Some(VarintTypeTag::U64),
+            (TYPE_BITS_UNSIGNED, SIZE_BITS_128) => Some(VarintTypeTag::U128),
+            (TYPE_BITS_SIGNED, SIZE_BITS_8) => Some(VarintTypeTag::I8),
+            (TYPE_BITS_SIGNED, SIZE_BITS_16) => Some(VarintTypeTag::I16),
+            (TYPE_BITS_SIGNED, SIZE_BITS_32) => Some(VarintTypeTag::I32),
+            (TYPE_BITS_SIGNED, SIZE_BITS_64) => Some(VarintTypeTag::I64),
+            (TYPE_BITS_SIGNED, SIZE_BITS_128) => Some(VarintTypeTag::I128),
+            _ => None,
+        }
+    }
+}
+
+/// Returns the type identifier byte that `VarintValue::to_bytes` writes for a tag
+///
+/// The high 3 bits hold the signedness and the low 5 bits the width.
+#[inline]
+pub const fn type_id_for(tag: VarintTypeTag) -> u8 {
+    tag.get_type_id()
+}
+
+/// Returns the tag for a type identifier byte, or `None` for reserved bit patterns
+#[inline]
+pub const fn tag_for_type_id(type_id: u8) -> Option<VarintTypeTag> {
+    VarintTypeTag::from_type_id(type_id)
+}
+
+/// Writer for a stream of `VarintValue`s that all share one type
+///
+/// The type byte is written once at the start, followed by the bare payload of
+/// each value, saving one byte per value compared to `to_bytes`.
+pub struct VarintValueWriter<'a> {
+    tag: VarintTypeTag,
+    buf: &'a mut [u8],
+    pos: usize,
+}
+
+impl<'a> VarintValueWriter<'a> {
+    /// Creates a writer for values of type `tag`, writing the type byte immediately
+    ///
+    /// # Errors
+    /// Returns `Error::BufferTooSmall` if the buffer is empty
+    pub fn new(tag: VarintTypeTag, buf: &'a mut [u8]) -> Result<Self, Error> {
+        if buf.is_empty() {
+            return Err(Error::buffer_too_small(1, 0));
+        }
+        
+        buf[0] = tag.get_type_id();
+        Ok(VarintValueWriter { tag, buf, pos: 1 })
+    }
+    
+    /// Gets the declared element type
+    pub fn type_tag(&self) -> VarintTypeTag {
+        self.tag
+    }
+    
+    /// Gets the number of bytes written so far, including the type byte
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+    
+    /// Writes the payload of one value
+    ///
+    /// Returns the number of bytes written
+    ///
+    /// # Errors
+    /// * Returns `Error::TypeMismatch` if the value's variant differs from the declared type
+    /// * Returns `Error::BufferTooSmall` if the buffer is too small
+    pub fn push(&mut self, value: VarintValue) -> Result<usize, Error> {
+        if value.type_tag() != self.tag {
+            return Err(Error::type_mismatch());
+        }
+        
+        let bytes_written = value.encode_payload(&mut self.buf[self.pos..])?;
+        self.pos += bytes_written;
+        Ok(bytes_written)
+    }
+}
+
+/// Reader for a stream written by `VarintValueWriter`
+pub struct VarintValueReader<'a> {
+    tag: VarintTypeTag,
+    buf: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> VarintValueReader<'a> {
+    /// Creates a reader, consuming the leading type byte
+    ///
+    /// # Errors
+    /// * Returns `Error::InputTooShort` if the buffer is empty
+    /// * Returns `Error::InvalidEncoding` if the type byte is not a valid type
+    pub fn new(buf: &'a [u8]) -> Result<Self, Error> {
+        let type_id = *buf.first().ok_or_else(Error::input_too_short)?;
+        let tag = VarintTypeTag::from_type_id(type_id).ok_or_else(|| Error::invalid_encoding(0))?;
+        
+        Ok(VarintValueReader { tag, buf, pos: 1 })
+    }
+    
+    /// Gets the element type declared by the stream
+    pub fn type_tag(&self) -> VarintTypeTag {
+        self.tag
+    }
+    
+    /// Gets the current position in the buffer, including the type byte
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+    
+    /// Returns true if every value has been read
+    pub fn is_empty(&self) -> bool {
+        self.pos >= self.buf.len()
+    }
+    
+    /// Reads the next value
+    ///
+    /// # Errors
+    /// * Returns `Error::InputTooShort` if no values remain
+    /// * Returns any error from decoding the payload
+    pub fn read(&mut self) -> Result<VarintValue, Error> {
+        let (value, bytes_read) = VarintValue::decode_payload(self.tag.get_type_id(), &self.buf[self.pos..])?;
+        self.pos += bytes_read;
+        Ok(value)
+    }
+}
+
+/// Decodes an untagged varint stream into typed values following a schema
+///
+/// Each schema entry selects the width and whether the value is read with
+/// `decode` (unsigned) or `decode_zigzag` (signed), so no type bytes are needed
+/// in the stream.
+///
+/// Returns the number of bytes read
+///
+/// # Errors
+/// * Returns `Error::BufferTooSmall` if `out` is shorter than `schema`, with the counts in values
+/// * Returns any error from decoding the values themselves
+pub fn decode_with_schema(buf: &[u8], schema: &[VarintTypeTag], out: &mut [VarintValue]) -> Result<usize, Error> {
+    if out.len() < schema.len() {
+        return Err(Error::buffer_too_small(schema.len(), out.len()));
+    }
+    
+    let mut pos = 0;
+    for (tag, slot) in schema.iter().zip(out.iter_mut()) {
+        let (value, bytes_read) = VarintValue::decode_payload(tag.get_type_id(), &buf[pos..])?;
+        *slot = value;
+        pos += bytes_read;
+    }
+    
+    Ok(pos)
+}
+
+/// Returns the number of bytes `to_bytes_batch` needs for these values
+///
+/// Each value is written as its type byte followed by the full payload. A
+/// batch can't use the compact single-byte zero form of `to_bytes`, since the
+/// next value's type byte would be read as the payload, so zeros take two bytes.
+pub fn batch_serialized_size(values: &[VarintValue]) -> usize {
+    values.iter().map(VarintValue::framed_size).sum()
+}
+
+/// Serializes values back to back into a byte buffer
+///
+/// Returns the number of bytes written, equal to `batch_serialized_size(values)`
+///
+/// # Errors
+/// Returns `Error::BufferTooSmall` if the buffer cannot hold every value; nothing is written
+pub fn to_bytes_batch(values: &[VarintValue], buf: &mut [u8]) -> Result<usize, Error> {
+    let needed = batch_serialized_size(values);
+    if buf.len() < needed {
+        return Err(Error::buffer_too_small(needed, buf.len()));
+    }
+    
+    let mut pos = 0;
+    for value in values {
+        pos += value.encode_framed(&mut buf[pos..])?;
+    }
+    
+    Ok(pos)
+}
+
+/// Deserializes values written by `to_bytes_batch`, reading until the buffer is used up
+///
+/// Returns the number of values decoded
+///
+/// # Errors
+/// * Returns `Error::BufferTooSmall` if `out` cannot hold all values, with the counts in values
+/// * Returns `Error::InvalidEncoding` if a type byte is not recognised
+/// * Returns any error from decoding the payloads
+pub fn from_bytes_batch(buf: &[u8], out: &mut [VarintValue]) -> Result<usize, Error> {
+    let mut pos = 0;
+    let mut count = 0;
+    
+    while pos < buf.len() {
+        let (value, bytes_read) = VarintValue::decode_payload(buf[pos], &buf[pos + 1..])?;
+        
+        // Keep counting past the end of out so the error reports the full size
+        if let Some(slot) = out.get_mut(count) {
+            *slot = value;
+        }
+        
+        pos += 1 + bytes_read;
+        count += 1;
+    }
+    
+    if count > out.len() {
+        return Err(Error::buffer_too_small(count, out.len()));
+    }
+    
+    Ok(count)
+}
+
+/// Iterates over values written by `to_bytes_batch`
+///
+/// Yields an error once and then stops, like `VarIntValuesIter`.
+pub fn varint_values_from(buf: &[u8]) -> impl Iterator<Item = Result<VarintValue, Error>> + '_ {
+    let mut pos = 0;
+    let mut finished = false;
+    
+    core::iter::from_fn(move || {
+        if finished || pos >= buf.len() {
+            return None;
+        }
+        
+        match VarintValue::decode_payload(buf[pos], &buf[pos + 1..]) {
+            Ok((value, bytes_read)) => {
+                pos += 1 + bytes_read;
+                Some(Ok(value))
+            }
+            Err(e) => {
+                finished = true;
+                Some(Err(e))
+            }
+        }
+    })
+}
+
 // Type encoding bits:
 // First 3 bits: Type info
 // Last 5 bits: Value type info
 const TYPE_BITS_UNSIGNED: u8 = 0b000_00000;
 const TYPE_BITS_SIGNED: u8   = 0b001_00000;
+// Length-prefixed byte strings, shared by `DynValue` and `VarintValueRef`
+pub(crate) const TYPE_BITS_BYTES: u8 = 0b010_00000;
 
 // Size bits
 const SIZE_BITS_8: u8    = 0b000_00000;
@@ -67,6 +368,150 @@ impl VarintValue {
         )
     }
     
+    /// Returns the tag naming this value's variant
+    #[inline]
+    pub fn type_tag(&self) -> VarintTypeTag {
+        match self {
+            VarintValue::U8(_) => VarintTypeTag::U8,
+            VarintValue::U16(_) => VarintTypeTag::U16,
+            VarintValue::U32(_) => VarintTypeTag::U32,
+            VarintValue::U64(_) => VarintTypeTag::U64,
+            VarintValue::U128(_) => VarintTypeTag::U128,
+            VarintValue::I8(_) => VarintTypeTag::I8,
+            VarintValue::I16(_) => VarintTypeTag::I16,
+            VarintValue::I32(_) => VarintTypeTag::I32,
+            VarintValue::I64(_) => VarintTypeTag::I64,
+            VarintValue::I128(_) => VarintTypeTag::I128,
+        }
+    }
+    
+    /// Returns true if this value holds a signed integer
+    #[inline]
+    pub fn is_signed(&self) -> bool {
+        matches!(self, 
+            VarintValue::I8(_) | VarintValue::I16(_) | VarintValue::I32(_) | 
+            VarintValue::I64(_) | VarintValue::I128(_)
+        )
+    }
+    
+    /// Returns true if this value holds an unsigned integer
+    #[inline]
+    pub fn is_unsigned(&self) -> bool {
+        !self.is_signed()
+    }
+    
+    /// Returns the value as a `u64` if it is unsigned and fits
+    #[inline]
+    pub fn as_u64(&self) -> Option<u64> {
+        match self {
+            VarintValue::U8(val) => Some(*val as u64),
+            VarintValue::U16(val) => Some(*val as u64),
+            VarintValue::U32(val) => Some(*val as u64),
+            VarintValue::U64(val) => Some(*val),
+            VarintValue::U128(val) => u64::try_from(*val).ok(),
+            _ => None,
+        }
+    }
+    
+    /// Returns the value as an `i64` if it is signed and fits
+    #[inline]
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            VarintValue::I8(val) => Some(*val as i64),
+            VarintValue::I16(val) => Some(*val as i64),
+            VarintValue::I32(val) => Some(*val as i64),
+            VarintValue::I64(val) => Some(*val),
+            VarintValue::I128(val) => i64::try_from(*val).ok(),
+            _ => None,
+        }
+    }
+    
+    /// Widens any unsigned variant to a `u128`
+    ///
+    /// # Errors
+    /// Returns `Error::TypeMismatch` if the value is signed
+    #[inline]
+    pub fn try_into_u128(&self) -> Result<u128, Error> {
+        match self {
+            VarintValue::U8(val) => Ok(*val as u128),
+            VarintValue::U16(val) => Ok(*val as u128),
+            VarintValue::U32(val) => Ok(*val as u128),
+            VarintValue::U64(val) => Ok(*val as u128),
+            VarintValue::U128(val) => Ok(*val),
+            _ => Err(Error::type_mismatch()),
+        }
+    }
+    
+    /// Re-tags the value as the narrowest variant of the same signedness that holds it
+    ///
+    /// Returns `None` if the value is already in its narrowest variant, for
+    /// example `U64(300)` narrows to `U16(300)` but `U8(5)` stays as it is.
+    pub fn narrow(&self) -> Option<VarintValue> {
+        let candidates = if self.is_signed() {
+            [VarintTypeTag::I8, VarintTypeTag::I16, VarintTypeTag::I32, VarintTypeTag::I64]
+        } else {
+            [VarintTypeTag::U8, VarintTypeTag::U16, VarintTypeTag::U32, VarintTypeTag::U64]
+        };
+        
+        candidates.into_iter()
+            .find_map(|tag| self.retag(tag))
+            .filter(|narrowed| narrowed.type_tag() != self.type_tag())
+    }
+    
+    /// Re-tags the value as a variant at least as wide as its current one
+    ///
+    /// Returns `None` if `tag` has a different signedness or is narrower than
+    /// the current variant. Widening to the current tag returns the value unchanged.
+    pub fn widen_to(&self, tag: VarintTypeTag) -> Option<VarintValue> {
+        if tag.get_type_id() & 0b000_11111 < self.get_type_id() & 0b000_11111 {
+            return None;
+        }
+        
+        self.retag(tag)
+    }
+    
+    /// Creates the narrowest unsigned variant that holds the value
+    ///
+    /// For example `300` becomes `U16(300)` and `5` becomes `U8(5)`.
+    pub fn narrowest_unsigned(value: u128) -> VarintValue {
+        let wide = VarintValue::U128(value);
+        wide.narrow().unwrap_or(wide)
+    }
+    
+    /// Creates the narrowest signed variant that holds the value
+    ///
+    /// For example `-1` becomes `I8(-1)` and `-129` becomes `I16(-129)`.
+    pub fn narrowest_signed(value: i128) -> VarintValue {
+        let wide = VarintValue::I128(value);
+        wide.narrow().unwrap_or(wide)
+    }
+    
+    // Converts the value to the variant named by `tag`, if the signedness
+    // matches and the value fits
+    fn retag(&self, tag: VarintTypeTag) -> Option<VarintValue> {
+        if let Ok(val) = self.try_into_u128() {
+            return match tag {
+                VarintTypeTag::U8 => u8::try_from(val).ok().map(VarintValue::U8),
+                VarintTypeTag::U16 => u16::try_from(val).ok().map(VarintValue::U16),
+                VarintTypeTag::U32 => u32::try_from(val).ok().map(VarintValue::U32),
+                VarintTypeTag::U64 => u64::try_from(val).ok().map(VarintValue::U64),
+                VarintTypeTag::U128 => Some(VarintValue::U128(val)),
+                _ => None,
+            };
+        }
+        
+        // Signed values are exact in the ordering key
+        let (_, val) = self.ordering_key();
+        match tag {
+            VarintTypeTag::I8 => i8::try_from(val).ok().map(VarintValue::I8),
+            VarintTypeTag::I16 => i16::try_from(val).ok().map(VarintValue::I16),
+            VarintTypeTag::I32 => i32::try_from(val).ok().map(VarintValue::I32),
+            VarintTypeTag::I64 => i64::try_from(val).ok().map(VarintValue::I64),
+            VarintTypeTag::I128 => Some(VarintValue::I128(val)),
+            _ => None,
+        }
+    }
+    
     /// Directly calculate the number of bytes needed to encode this value
     #[inline]
     fn direct_size_calculation(&self) -> usize {
@@ -87,31 +532,31 @@ impl VarintValue {
             VarintValue::U8(val) => {
                 if *val == 0 { 1 } else {
                     let bits = 8 - val.leading_zeros() as usize;
-                    (bits + 6) / 7
+                    bits.div_ceil(7)
                 }
             },
             VarintValue::U16(val) => {
                 if *val == 0 { 1 } else {
                     let bits = 16 - val.leading_zeros() as usize;
-                    (bits + 6) / 7
+                    bits.div_ceil(7)
                 }
             },
             VarintValue::U32(val) => {
                 if *val == 0 { 1 } else {
                     let bits = 32 - val.leading_zeros() as usize;
-                    (bits + 6) / 7
+                    bits.div_ceil(7)
                 }
             },
             VarintValue::U64(val) => {
                 if *val == 0 { 1 } else {
                     let bits = 64 - val.leading_zeros() as usize;
-                    (bits + 6) / 7
+                    bits.div_ceil(7)
                 }
             },
             VarintValue::U128(val) => {
                 if *val == 0 { 1 } else {
                     let bits = 128 - val.leading_zeros() as usize;
-                    (bits + 6) / 7
+                    bits.div_ceil(7)
                 }
             },
             
@@ -120,35 +565,35 @@ impl VarintValue {
                 let zigzag_val = ((val << 1) ^ (val >> 7)) as u8;
                 if zigzag_val == 0 { 1 } else {
                     let bits = 8 - zigzag_val.leading_zeros() as usize;
-                    (bits + 6) / 7
+                    bits.div_ceil(7)
                 }
             },
             VarintValue::I16(val) => {
                 let zigzag_val = ((val << 1) ^ (val >> 15)) as u16;
                 if zigzag_val == 0 { 1 } else {
                     let bits = 16 - zigzag_val.leading_zeros() as usize;
-                    (bits + 6) / 7
+                    bits.div_ceil(7)
                 }
             },
             VarintValue::I32(val) => {
                 let zigzag_val = ((val << 1) ^ (val >> 31)) as u32;
                 if zigzag_val == 0 { 1 } else {
                     let bits = 32 - zigzag_val.leading_zeros() as usize;
-                    (bits + 6) / 7
+                    bits.div_ceil(7)
                 }
             },
             VarintValue::I64(val) => {
                 let zigzag_val = ((val << 1) ^ (val >> 63)) as u64;
                 if zigzag_val == 0 { 1 } else {
                     let bits = 64 - zigzag_val.leading_zeros() as usize;
-                    (bits + 6) / 7
+                    bits.div_ceil(7)
                 }
             },
             VarintValue::I128(val) => {
                 let zigzag_val = ((val << 1) ^ (val >> 127)) as u128;
                 if zigzag_val == 0 { 1 } else {
                     let bits = 128 - zigzag_val.leading_zeros() as usize;
-                    (bits + 6) / 7
+                    bits.div_ceil(7)
                 }
             },
         };
@@ -176,10 +621,7 @@ impl VarintValue {
     #[inline]
     pub fn to_bytes(&self, buffer: &mut [u8]) -> Result<usize, Error> {
         if buffer.is_empty() {
-            return Err(Error::BufferTooSmall { 
-                needed: 1,
-                actual: 0
-            });
+            return Err(Error::buffer_too_small(1, 0));
         }
         
         // 优化：处理零值的特殊情况
@@ -199,85 +641,62 @@ impl VarintValue {
         buffer[0] = self.get_type_id();
         
         // 直接编码到缓冲区，避免临时缓冲区
+        Ok(self.encode_payload(&mut buffer[1..])? + 1) // +1 表示类型字节
+    }
+    
+    /// Encodes only the integer payload of this value, without the type byte.
+    ///
+    /// Unlike `to_bytes`, zero values are written out in full, so the payload
+    /// is always at least one byte long.
+    #[inline]
+    pub(crate) fn encode_payload(&self, buffer: &mut [u8]) -> Result<usize, Error> {
         match self {
-            // 无符号类型使用标准编码
-            VarintValue::U8(val) => {
-                let result = encode(*val, &mut buffer[1..]);
-                match result {
-                    Ok(bytes_written) => Ok(bytes_written + 1), // +1 表示类型字节
-                    Err(e) => Err(e),
-                }
-            },
-            VarintValue::U16(val) => {
-                let result = encode(*val, &mut buffer[1..]);
-                match result {
-                    Ok(bytes_written) => Ok(bytes_written + 1),
-                    Err(e) => Err(e),
-                }
-            },
-            VarintValue::U32(val) => {
-                let result = encode(*val, &mut buffer[1..]);
-                match result {
-                    Ok(bytes_written) => Ok(bytes_written + 1),
-                    Err(e) => Err(e),
-                }
-            },
-            VarintValue::U64(val) => {
-                let result = encode(*val, &mut buffer[1..]);
-                match result {
-                    Ok(bytes_written) => Ok(bytes_written + 1),
-                    Err(e) => Err(e),
-                }
-            },
-            VarintValue::U128(val) => {
-                let result = encode(*val, &mut buffer[1..]);
-                match result {
-                    Ok(bytes_written) => Ok(bytes_written + 1),
-                    Err(e) => Err(e),
-                }
-            },
+            // Unsigned types use standard encoding
+            VarintValue::U8(val) => encode(*val, buffer),
+            VarintValue::U16(val) => encode(*val, buffer),
+            VarintValue::U32(val) => encode(*val, buffer),
+            VarintValue::U64(val) => encode(*val, buffer),
+            VarintValue::U128(val) => encode(*val, buffer),
             
-            // 有符号类型使用zigzag编码
-            VarintValue::I8(val) => {
-                let result = encode_zigzag(*val, &mut buffer[1..]);
-                match result {
-                    Ok(bytes_written) => Ok(bytes_written + 1),
-                    Err(e) => Err(e),
-                }
-            },
-            VarintValue::I16(val) => {
-                let result = encode_zigzag(*val, &mut buffer[1..]);
-                match result {
-                    Ok(bytes_written) => Ok(bytes_written + 1),
-                    Err(e) => Err(e),
-                }
-            },
-            VarintValue::I32(val) => {
-                let result = encode_zigzag(*val, &mut buffer[1..]);
-                match result {
-                    Ok(bytes_written) => Ok(bytes_written + 1),
-                    Err(e) => Err(e),
-                }
-            },
-            VarintValue::I64(val) => {
-                let result = encode_zigzag(*val, &mut buffer[1..]);
-                match result {
-                    Ok(bytes_written) => Ok(bytes_written + 1),
-                    Err(e) => Err(e),
-                }
-            },
-            VarintValue::I128(val) => {
-                let result = encode_zigzag(*val, &mut buffer[1..]);
-                match result {
-                    Ok(bytes_written) => Ok(bytes_written + 1),
-                    Err(e) => Err(e),
-                }
-            },
+            // Signed types use zigzag encoding
+            VarintValue::I8(val) => encode_zigzag(*val, buffer),
+            VarintValue::I16(val) => encode_zigzag(*val, buffer),
+            VarintValue::I32(val) => encode_zigzag(*val, buffer),
+            VarintValue::I64(val) => encode_zigzag(*val, buffer),
+            VarintValue::I128(val) => encode_zigzag(*val, buffer),
         }
     }
     
+    /// Returns the size of the framed form written by `encode_framed`
+    ///
+    /// The integer payload is always written, so zero still takes a byte.
+    #[inline]
+    pub(crate) fn framed_size(&self) -> usize {
+        self.serialized_size().max(2)
+    }
+    
+    /// Writes the type byte followed by the full payload.
+    ///
+    /// This is the framing used wherever values sit back to back, since the
+    /// compact zero of `to_bytes` would swallow the next byte as its payload.
+    #[inline]
+    pub(crate) fn encode_framed(&self, buffer: &mut [u8]) -> Result<usize, Error> {
+        let needed = self.framed_size();
+        if buffer.len() < needed {
+            return Err(Error::buffer_too_small(needed, buffer.len()));
+        }
+        
+        buffer[0] = self.get_type_id();
+        Ok(1 + self.encode_payload(&mut buffer[1..])?)
+    }
+    
     /// Deserializes a value from a byte buffer.
     ///
+    /// The type byte is validated before anything else: only the signed flag
+    /// may be set in the high 3 bits, and the size code must be 0-4. Anything
+    /// else, including bits in the unused `0b000_11000` region, is rejected
+    /// with `Error::InvalidEncoding`.
+    ///
     /// # Arguments
     /// * `bytes` - The byte buffer to read from
     ///
@@ -287,13 +706,18 @@ impl VarintValue {
     #[inline]
     pub fn from_bytes(bytes: &[u8]) -> Result<(Self, usize), Error> {
         if bytes.is_empty() {
-            return Err(Error::InputTooShort);
+            return Err(Error::input_too_short());
         }
         
         let type_byte = bytes[0];
         let type_bits = type_byte & 0b111_00000; // Get high 3 bits
         let size_bits = type_byte & 0b000_11111; // Get low 5 bits
         
+        // Reject undefined type bits and size codes up front
+        if (type_bits != TYPE_BITS_UNSIGNED && type_bits != TYPE_BITS_SIGNED) || size_bits > SIZE_BITS_128 {
+            return Err(Error::invalid_encoding(0));
+        }
+        
         let data = &bytes[1..];
         
         // Check if it's the special case for zero
@@ -310,57 +734,271 @@ impl VarintValue {
                 (TYPE_BITS_SIGNED, SIZE_BITS_32) => return Ok((VarintValue::I32(0), 1)),
                 (TYPE_BITS_SIGNED, SIZE_BITS_64) => return Ok((VarintValue::I64(0), 1)),
                 (TYPE_BITS_SIGNED, SIZE_BITS_128) => return Ok((VarintValue::I128(0), 1)),
-                _ => return Err(Error::InvalidEncoding),
+                _ => return Err(Error::invalid_encoding(0)),
             }
         }
         
         // Regular decoding based on type
+        let (value, bytes_read) = Self::decode_payload(type_byte, data)?;
+        Ok((value, bytes_read + 1))
+    }
+    
+    /// Deserializes a buffer that must hold exactly one value
+    ///
+    /// Unlike `from_bytes`, bytes left after the value are an error, so
+    /// framing bugs are not silently ignored.
+    ///
+    /// # Errors
+    /// * Returns `Error::TrailingData` if bytes remain after the value
+    /// * Returns any error from `from_bytes`
+    pub fn from_bytes_exact(bytes: &[u8]) -> Result<Self, Error> {
+        let (value, bytes_read) = Self::from_bytes(bytes)?;
+        
+        if bytes_read != bytes.len() {
+            return Err(Error::trailing_data());
+        }
+        
+        Ok(value)
+    }
+    
+    /// Serializes the value like `to_bytes`, but with signed variants written as
+    /// two's complement signed LEB128 instead of zigzag.
+    ///
+    /// The type byte, the unsigned variants and the compact zero form are the
+    /// same as `to_bytes`. Every other signed value is encoded differently:
+    /// zigzag doubles positives and maps negatives to odd numbers, while signed
+    /// LEB128 sign-extends, so `I32(-1)` is `0x01` under zigzag but `0x7F` here.
+    ///
+    /// # Arguments
+    /// * `buffer` - The buffer to write into
+    ///
+    /// # Returns
+    /// * `Ok(size)` - The number of bytes written
+    /// * `Err(...)` - If the buffer is too small
+    pub fn to_bytes_leb128(&self, buffer: &mut [u8]) -> Result<usize, Error> {
+        if buffer.is_empty() {
+            return Err(Error::buffer_too_small(1, 0));
+        }
+        
+        buffer[0] = self.get_type_id();
+        let data = &mut buffer[1..];
+        let bytes_written = match *self {
+            VarintValue::I8(val) if val != 0 => encode_leb128_signed(val, data),
+            VarintValue::I16(val) if val != 0 => encode_leb128_signed(val, data),
+            VarintValue::I32(val) if val != 0 => encode_leb128_signed(val, data),
+            VarintValue::I64(val) if val != 0 => encode_leb128_signed(val, data),
+            VarintValue::I128(val) if val != 0 => encode_leb128_signed(val, data),
+            // Unsigned values and zeros are written exactly as by to_bytes
+            _ => return self.to_bytes(buffer),
+        }?;
+        
+        Ok(bytes_written + 1)
+    }
+    
+    /// Deserializes a value written by `to_bytes_leb128`.
+    ///
+    /// # Arguments
+    /// * `bytes` - The byte buffer to read from
+    ///
+    /// # Returns
+    /// * `Ok((value, size))` - The deserialized value and number of bytes read
+    /// * `Err(...)` - If decoding fails
+    pub fn from_bytes_leb128(bytes: &[u8]) -> Result<(Self, usize), Error> {
+        // Unsigned values, compact zeros and invalid type bytes match from_bytes
+        if bytes.len() < 2 || bytes[0] & 0b111_00000 != TYPE_BITS_SIGNED {
+            return Self::from_bytes(bytes);
+        }
+        
+        let data = &bytes[1..];
+        let (value, bytes_read) = match bytes[0] & 0b000_11111 {
+            SIZE_BITS_8 => decode_leb128_signed::<i8>(data).map(|(val, n)| (VarintValue::I8(val), n)),
+            SIZE_BITS_16 => decode_leb128_signed::<i16>(data).map(|(val, n)| (VarintValue::I16(val), n)),
+            SIZE_BITS_32 => decode_leb128_signed::<i32>(data).map(|(val, n)| (VarintValue::I32(val), n)),
+            SIZE_BITS_64 => decode_leb128_signed::<i64>(data).map(|(val, n)| (VarintValue::I64(val), n)),
+            SIZE_BITS_128 => decode_leb128_signed::<i128>(data).map(|(val, n)| (VarintValue::I128(val), n)),
+            _ => Err(Error::invalid_encoding(0)),
+        }?;
+        
+        Ok((value, bytes_read + 1))
+    }
+    
+    /// Decodes an integer payload written by `encode_payload` for the given type byte.
+    ///
+    /// Returns the decoded value and the number of payload bytes read.
+    #[inline]
+    pub(crate) fn decode_payload(type_byte: u8, data: &[u8]) -> Result<(Self, usize), Error> {
+        let type_bits = type_byte & 0b111_00000;
+        let size_bits = type_byte & 0b000_11111;
+        
         match (type_bits, size_bits) {
             (TYPE_BITS_UNSIGNED, SIZE_BITS_8) => {
                 let (val, bytes_read) = decode::<u8>(data)?;
-                Ok((VarintValue::U8(val), bytes_read + 1))
+                Ok((VarintValue::U8(val), bytes_read))
             },
             (TYPE_BITS_UNSIGNED, SIZE_BITS_16) => {
                 let (val, bytes_read) = decode::<u16>(data)?;
-                Ok((VarintValue::U16(val), bytes_read + 1))
+                Ok((VarintValue::U16(val), bytes_read))
             },
             (TYPE_BITS_UNSIGNED, SIZE_BITS_32) => {
                 let (val, bytes_read) = decode::<u32>(data)?;
-                Ok((VarintValue::U32(val), bytes_read + 1))
+                Ok((VarintValue::U32(val), bytes_read))
             },
             (TYPE_BITS_UNSIGNED, SIZE_BITS_64) => {
                 let (val, bytes_read) = decode::<u64>(data)?;
-                Ok((VarintValue::U64(val), bytes_read + 1))
+                Ok((VarintValue::U64(val), bytes_read))
             },
             (TYPE_BITS_UNSIGNED, SIZE_BITS_128) => {
                 let (val, bytes_read) = decode::<u128>(data)?;
-                Ok((VarintValue::U128(val), bytes_read + 1))
+                Ok((VarintValue::U128(val), bytes_read))
             },
             (TYPE_BITS_SIGNED, SIZE_BITS_8) => {
                 let (val, bytes_read) = decode_zigzag::<i8>(data)?;
-                Ok((VarintValue::I8(val), bytes_read + 1))
+                Ok((VarintValue::I8(val), bytes_read))
             },
             (TYPE_BITS_SIGNED, SIZE_BITS_16) => {
                 let (val, bytes_read) = decode_zigzag::<i16>(data)?;
-                Ok((VarintValue::I16(val), bytes_read + 1))
+                Ok((VarintValue::I16(val), bytes_read))
             },
             (TYPE_BITS_SIGNED, SIZE_BITS_32) => {
                 let (val, bytes_read) = decode_zigzag::<i32>(data)?;
-                Ok((VarintValue::I32(val), bytes_read + 1))
+                Ok((VarintValue::I32(val), bytes_read))
             },
             (TYPE_BITS_SIGNED, SIZE_BITS_64) => {
                 let (val, bytes_read) = decode_zigzag::<i64>(data)?;
-                Ok((VarintValue::I64(val), bytes_read + 1))
+                Ok((VarintValue::I64(val), bytes_read))
             },
             (TYPE_BITS_SIGNED, SIZE_BITS_128) => {
                 let (val, bytes_read) = decode_zigzag::<i128>(data)?;
-                Ok((VarintValue::I128(val), bytes_read + 1))
+                Ok((VarintValue::I128(val), bytes_read))
             },
-            _ => Err(Error::InvalidEncoding),
+            _ => Err(Error::invalid_encoding(0)),
         }
     }
 }
 
+// Applies a checked integer method to two values of the same variant,
+// returning None for mismatched variants
+macro_rules! checked_same_variant {
+    ($lhs:expr, $rhs:expr, $method:ident) => {
+        match ($lhs, $rhs) {
+            (VarintValue::U8(a), VarintValue::U8(b)) => a.$method(*b).map(VarintValue::U8),
+            (VarintValue::U16(a), VarintValue::U16(b)) => a.$method(*b).map(VarintValue::U16),
+            (VarintValue::U32(a), VarintValue::U32(b)) => a.$method(*b).map(VarintValue::U32),
+            (VarintValue::U64(a), VarintValue::U64(b)) => a.$method(*b).map(VarintValue::U64),
+            (VarintValue::U128(a), VarintValue::U128(b)) => a.$method(*b).map(VarintValue::U128),
+            (VarintValue::I8(a), VarintValue::I8(b)) => a.$method(*b).map(VarintValue::I8),
+            (VarintValue::I16(a), VarintValue::I16(b)) => a.$method(*b).map(VarintValue::I16),
+            (VarintValue::I32(a), VarintValue::I32(b)) => a.$method(*b).map(VarintValue::I32),
+            (VarintValue::I64(a), VarintValue::I64(b)) => a.$method(*b).map(VarintValue::I64),
+            (VarintValue::I128(a), VarintValue::I128(b)) => a.$method(*b).map(VarintValue::I128),
+            _ => None,
+        }
+    };
+}
+
+impl VarintValue {
+    /// Adds two values of the same variant
+    ///
+    /// Returns `None` on overflow, or if the variants differ; mixed variants
+    /// are never promoted to a common type.
+    pub fn checked_add(&self, other: &Self) -> Option<Self> {
+        checked_same_variant!(self, other, checked_add)
+    }
+    
+    /// Subtracts two values of the same variant
+    ///
+    /// Returns `None` on overflow, or if the variants differ
+    pub fn checked_sub(&self, other: &Self) -> Option<Self> {
+        checked_same_variant!(self, other, checked_sub)
+    }
+    
+    /// Multiplies two values of the same variant
+    ///
+    /// Returns `None` on overflow, or if the variants differ
+    pub fn checked_mul(&self, other: &Self) -> Option<Self> {
+        checked_same_variant!(self, other, checked_mul)
+    }
+}
+
+impl VarintValue {
+    // Key for numeric ordering: the flag marks U128 values beyond i128::MAX,
+    // which are stored offset so they keep their relative order
+    #[inline]
+    fn ordering_key(&self) -> (bool, i128) {
+        match *self {
+            VarintValue::U8(val) => (false, val as i128),
+            VarintValue::U16(val) => (false, val as i128),
+            VarintValue::U32(val) => (false, val as i128),
+            VarintValue::U64(val) => (false, val as i128),
+            VarintValue::U128(val) => match i128::try_from(val) {
+                Ok(val) => (false, val),
+                Err(_) => (true, (val - i128::MAX as u128 - 1) as i128),
+            },
+            VarintValue::I8(val) => (false, val as i128),
+            VarintValue::I16(val) => (false, val as i128),
+            VarintValue::I32(val) => (false, val as i128),
+            VarintValue::I64(val) => (false, val as i128),
+            VarintValue::I128(val) => (false, val),
+        }
+    }
+}
+
+impl Ord for VarintValue {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.ordering_key()
+            .cmp(&other.ordering_key())
+            .then_with(|| self.get_type_id().cmp(&other.get_type_id()))
+    }
+}
+
+impl PartialOrd for VarintValue {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+// Display prints only the inner number, without the type tag
+impl fmt::Display for VarintValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for_all_types!(self, 
+            |val, _| fmt::Display::fmt(&val, f), 
+            |val, _| fmt::Display::fmt(&val, f)
+        )
+    }
+}
+
+// Implement From for each integer type, mapping to the matching variant
+macro_rules! impl_from_int {
+    ($($type:ty => $variant:ident),* $(,)?) => {
+        $(
+            impl From<$type> for VarintValue {
+                #[inline]
+                fn from(value: $type) -> Self {
+                    VarintValue::$variant(value)
+                }
+            }
+        )*
+    };
+}
+
+impl_from_int!(
+    u8 => U8, u16 => U16, u32 => U32, u64 => U64, u128 => U128,
+    i8 => I8, i16 => I16, i32 => I32, i64 => I64, i128 => I128,
+);
+
+/// Parses the first value of a buffer written by `VarintValue::to_bytes`
+///
+/// Any bytes after the first value are ignored; use `from_bytes` when the
+/// number of bytes read is needed.
+impl TryFrom<&[u8]> for VarintValue {
+    type Error = Error;
+    
+    #[inline]
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        VarintValue::from_bytes(bytes).map(|(value, _)| value)
+    }
+}
+
 /// Macro for creating VarintValue instances in a concise way
 #[macro_export]
 macro_rules! varint {
@@ -376,8 +1014,31 @@ macro_rules! varint {
     (i128: $val:expr) => { $crate::VarintValue::I128($val) };
 }
 
+// Extension trait for Result to help with unwrap_err_or_else in tests
+#[cfg(test)]
+trait ResultExt<T, E> {
+    fn unwrap_err_or_else<F>(self, f: F) -> T
+    where
+        F: FnOnce(&E) -> T;
+}
+
+#[cfg(test)]
+impl<T, E> ResultExt<T, E> for Result<T, E> {
+    fn unwrap_err_or_else<F>(self, f: F) -> T
+    where
+        F: FnOnce(&E) -> T,
+    {
+        match self {
+            Ok(t) => t,
+            Err(ref e) => f(e),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    extern crate std;
+    use self::std::format;
     use super::*;
     
     #[test]
@@ -396,7 +1057,7 @@ mod tests {
             let type_id = value.get_type_id();
             
             // Check if we can reconstruct the value's type from just the type ID
-            let mut dummy_buffer = [type_id, 0, 0, 0, 0]; // Just need the type byte
+            let dummy_buffer = [type_id, 0, 0, 0, 0]; // Just need the type byte
             let (decoded, _) = VarintValue::from_bytes(&dummy_buffer).unwrap_err_or_else(|_| {
                 // Only testing that the type is correctly identified
                 match value {
@@ -505,6 +1166,88 @@ mod tests {
         assert_eq!(varint!(i64: -1000000000), VarintValue::I64(-1000000000));
     }
     
+    #[test]
+    fn test_from_integers() {
+        assert_eq!(VarintValue::from(200u8), varint!(u8: 200));
+        assert_eq!(VarintValue::from(1000u16), varint!(u16: 1000));
+        assert_eq!(VarintValue::from(100000u32), varint!(u32: 100000));
+        assert_eq!(VarintValue::from(u64::MAX), varint!(u64: u64::MAX));
+        assert_eq!(VarintValue::from(u128::MAX), varint!(u128: u128::MAX));
+        assert_eq!(VarintValue::from(-100i8), varint!(i8: -100));
+        assert_eq!(VarintValue::from(-100i16), varint!(i16: -100));
+        assert_eq!(VarintValue::from(-100000i32), varint!(i32: -100000));
+        assert_eq!(VarintValue::from(i64::MIN), varint!(i64: i64::MIN));
+        assert_eq!(VarintValue::from(i128::MIN), varint!(i128: i128::MIN));
+        
+        let value: VarintValue = 42u32.into();
+        assert_eq!(value, VarintValue::U32(42));
+    }
+    
+    #[test]
+    fn test_extraction() {
+        // Widening within the same signedness
+        assert_eq!(VarintValue::U8(5).as_u64(), Some(5));
+        assert_eq!(VarintValue::U128(u64::MAX as u128).as_u64(), Some(u64::MAX));
+        assert_eq!(VarintValue::I16(-300).as_i64(), Some(-300));
+        assert_eq!(VarintValue::U32(7).try_into_u128(), Ok(7));
+        
+        // Out of range or wrong signedness
+        assert_eq!(VarintValue::U128(u128::MAX).as_u64(), None);
+        assert_eq!(VarintValue::I128(i128::MIN).as_i64(), None);
+        assert_eq!(VarintValue::I8(5).as_u64(), None);
+        assert_eq!(VarintValue::U8(5).as_i64(), None);
+        assert_eq!(VarintValue::I64(1).try_into_u128(), Err(Error::TypeMismatch));
+        
+        assert!(VarintValue::I32(-1).is_signed());
+        assert!(!VarintValue::I32(-1).is_unsigned());
+        assert!(VarintValue::U16(1).is_unsigned());
+        assert!(!VarintValue::U16(1).is_signed());
+    }
+    
+    #[test]
+    fn test_display() {
+        assert_eq!(format!("{}", varint!(u64: 1000)), "1000");
+        assert_eq!(format!("{}", varint!(i16: -100)), "-100");
+        assert_eq!(format!("{}", varint!(u128: u128::MAX)), "340282366920938463463374607431768211455");
+        assert_eq!(format!("{:>5}", varint!(u8: 7)), "    7");
+    }
+    
+    #[test]
+    fn test_ordering() {
+        // Numeric comparison across signedness and widths
+        assert!(VarintValue::U8(200) > VarintValue::I8(-5));
+        assert!(VarintValue::I64(-1) < VarintValue::U8(0));
+        assert!(VarintValue::I128(i128::MIN) < VarintValue::I8(i8::MIN));
+        assert!(VarintValue::U16(300) > VarintValue::I32(299));
+        
+        // U128 above i128::MAX is greater than everything else
+        let huge = VarintValue::U128(i128::MAX as u128 + 1);
+        assert!(huge > VarintValue::I128(i128::MAX));
+        assert!(huge > VarintValue::U128(i128::MAX as u128));
+        assert!(VarintValue::U128(u128::MAX) > huge);
+        
+        // Numerically equal values of different types tie-break on the type id
+        assert!(VarintValue::U8(5) < VarintValue::U16(5));
+        assert!(VarintValue::U64(5) < VarintValue::I8(5));
+        assert_eq!(VarintValue::U32(5).cmp(&VarintValue::U32(5)), Ordering::Equal);
+        
+        let mut values = [
+            VarintValue::U128(u128::MAX),
+            VarintValue::U8(200),
+            VarintValue::I8(-5),
+            VarintValue::I128(i128::MIN),
+            VarintValue::U32(0),
+        ];
+        values.sort();
+        assert_eq!(values, [
+            VarintValue::I128(i128::MIN),
+            VarintValue::I8(-5),
+            VarintValue::U32(0),
+            VarintValue::U8(200),
+            VarintValue::U128(u128::MAX),
+        ]);
+    }
+    
     #[test]
     fn test_serialized_size() {
         let value = VarintValue::U64(128);
@@ -534,25 +1277,309 @@ mod tests {
         let invalid = [0xFF, 0x00];
         assert!(VarintValue::from_bytes(&invalid).is_err());
     }
-}
-
-// Extension trait for Result to help with unwrap_err_or_else in tests
-#[cfg(test)]
-trait ResultExt<T, E> {
-    fn unwrap_err_or_else<F>(self, f: F) -> T
-    where
-        F: FnOnce(&E) -> T;
-}
-
-#[cfg(test)]
-impl<T, E> ResultExt<T, E> for Result<T, E> {
-    fn unwrap_err_or_else<F>(self, f: F) -> T
-    where
-        F: FnOnce(&E) -> T,
-    {
-        match self {
-            Ok(t) => t,
-            Err(ref e) => f(e),
+    
+    #[test]
+    fn test_decode_with_schema() {
+        let mut buf = [0u8; 16];
+        let mut pos = encode(300u32, &mut buf).unwrap();
+        pos += encode_zigzag(-2i16, &mut buf[pos..]).unwrap();
+        pos += encode(7u8, &mut buf[pos..]).unwrap();
+        
+        let schema = [VarintTypeTag::U32, VarintTypeTag::I16, VarintTypeTag::U8];
+        let mut out = [VarintValue::U8(0); 3];
+        assert_eq!(decode_with_schema(&buf[..pos], &schema, &mut out), Ok(pos));
+        assert_eq!(out, [varint!(u32: 300), varint!(i16: -2), varint!(u8: 7)]);
+        
+        for value in out {
+            assert_eq!(value.type_tag().get_type_id(), value.get_type_id());
+        }
+        
+        // Widths are checked per entry
+        let narrow = [VarintTypeTag::U8];
+        assert_eq!(decode_with_schema(&buf[..pos], &narrow, &mut out), Err(Error::Overflow { bytes_read: 2 }));
+        
+        let mut small = [VarintValue::U8(0); 2];
+        assert_eq!(
+            decode_with_schema(&buf[..pos], &schema, &mut small),
+            Err(Error::BufferTooSmall { needed: 3, actual: 2 })
+        );
+        assert_eq!(decode_with_schema(&buf[..2], &schema, &mut out), Err(Error::InputTooShort));
+    }
+    
+    #[test]
+    fn test_hash_map_keys() {
+        use self::std::collections::HashMap;
+        
+        let mut map = HashMap::new();
+        map.insert(VarintValue::U8(5), "u8");
+        map.insert(VarintValue::U32(5), "u32");
+        map.insert(VarintValue::I8(5), "i8");
+        map.insert(VarintValue::I128(-1), "i128");
+        assert_eq!(map.len(), 4);
+        
+        // Equal values hit the same entry
+        map.insert(VarintValue::U8(5), "u8 again");
+        assert_eq!(map.len(), 4);
+        assert_eq!(map.get(&varint!(u8: 5)), Some(&"u8 again"));
+        assert_eq!(map.get(&VarintValue::U32(5)), Some(&"u32"));
+        assert_eq!(map.get(&VarintValue::from(-1i128)), Some(&"i128"));
+        assert_eq!(map.get(&VarintValue::U16(5)), None);
+    }
+    
+    #[test]
+    fn test_batch_round_trip() {
+        let values = [
+            varint!(u8: 127),
+            varint!(u16: 0),
+            varint!(i32: -100000),
+            varint!(u128: u128::MAX),
+            varint!(i8: 0),
+            varint!(i64: i64::MIN),
+        ];
+        
+        let mut buf = [0u8; 64];
+        let bytes_written = to_bytes_batch(&values, &mut buf).unwrap();
+        assert_eq!(bytes_written, batch_serialized_size(&values));
+        
+        // Zeros take their full payload inside a batch
+        let compact: usize = values.iter().map(VarintValue::serialized_size).sum();
+        assert_eq!(bytes_written, compact + 2);
+        
+        let mut out = [VarintValue::U8(0); 8];
+        assert_eq!(from_bytes_batch(&buf[..bytes_written], &mut out), Ok(values.len()));
+        assert_eq!(&out[..values.len()], &values[..]);
+        
+        let mut small_out = [VarintValue::U8(0); 4];
+        assert_eq!(
+            from_bytes_batch(&buf[..bytes_written], &mut small_out),
+            Err(Error::BufferTooSmall { needed: 6, actual: 4 })
+        );
+        
+        let mut small_buf = [0u8; 8];
+        assert_eq!(
+            to_bytes_batch(&values, &mut small_buf),
+            Err(Error::BufferTooSmall { needed: bytes_written, actual: 8 })
+        );
+        
+        // A truncated batch is rejected
+        assert!(from_bytes_batch(&buf[..bytes_written - 1], &mut out).is_err());
+        assert_eq!(from_bytes_batch(&[], &mut out), Ok(0));
+    }
+    
+    #[test]
+    fn test_rejects_undefined_type_bytes() {
+        for type_byte in [0xFF, 0x05, 0x1F, 0x08, 0x18, 0x40, 0x25] {
+            // Both in the compact zero form and followed by a payload
+            assert_eq!(VarintValue::from_bytes(&[type_byte]), Err(Error::InvalidEncoding { offset: 0 }));
+            assert_eq!(VarintValue::from_bytes(&[type_byte, 0x01]), Err(Error::InvalidEncoding { offset: 0 }));
+        }
+        
+        // Every defined type byte is still accepted
+        for type_byte in [0x00, 0x01, 0x02, 0x03, 0x04, 0x20, 0x21, 0x22, 0x23, 0x24] {
+            assert!(VarintValue::from_bytes(&[type_byte]).is_ok());
+            assert!(VarintValue::from_bytes(&[type_byte, 0x01]).is_ok());
+        }
+    }
+    
+    #[test]
+    fn test_leb128_signed_mode() {
+        let mut zigzag = [0u8; 20];
+        let mut leb128 = [0u8; 20];
+        
+        let value = VarintValue::I32(-1);
+        let zigzag_len = value.to_bytes(&mut zigzag).unwrap();
+        let leb128_len = value.to_bytes_leb128(&mut leb128).unwrap();
+        assert_eq!(&zigzag[..zigzag_len], &[0x22, 0x01]);
+        assert_eq!(&leb128[..leb128_len], &[0x22, 0x7F]);
+        
+        assert_eq!(VarintValue::from_bytes(&zigzag[..zigzag_len]), Ok((value, 2)));
+        assert_eq!(VarintValue::from_bytes_leb128(&leb128[..leb128_len]), Ok((value, 2)));
+        
+        for value in [
+            varint!(i8: i8::MIN), varint!(i16: 64), varint!(i64: -65),
+            varint!(i128: i128::MAX), varint!(u32: 300), varint!(i32: 0), varint!(u8: 0),
+        ] {
+            let bytes_written = value.to_bytes_leb128(&mut leb128).unwrap();
+            assert_eq!(VarintValue::from_bytes_leb128(&leb128[..bytes_written]), Ok((value, bytes_written)));
+        }
+        
+        // Unsigned values and zeros are identical in both modes
+        for value in [varint!(u64: 1000), varint!(i16: 0)] {
+            let zigzag_len = value.to_bytes(&mut zigzag).unwrap();
+            let leb128_len = value.to_bytes_leb128(&mut leb128).unwrap();
+            assert_eq!(&zigzag[..zigzag_len], &leb128[..leb128_len]);
+        }
+        
+        assert_eq!(VarintValue::from_bytes_leb128(&[0x25, 0x01]), Err(Error::InvalidEncoding { offset: 0 }));
+        assert!(VarintValue::I8(-1).to_bytes_leb128(&mut []).is_err());
+    }
+    
+    #[test]
+    fn test_homogeneous_writer_reader() {
+        let mut compact = [0u8; 512];
+        let mut writer = VarintValueWriter::new(VarintTypeTag::U32, &mut compact).unwrap();
+        let mut tagged_size = 0;
+        
+        for i in 0..100u32 {
+            let value = VarintValue::U32(i * 1000);
+            writer.push(value).unwrap();
+            tagged_size += value.serialized_size();
         }
+        
+        // One type byte for the stream instead of 100; the leading zero needs
+        // its payload byte here, where to_bytes folds it into the type byte
+        let compact_len = writer.position();
+        assert_eq!(compact_len, tagged_size - 100 + 1 + 1);
+        assert!(compact_len < tagged_size);
+        
+        assert_eq!(writer.push(VarintValue::U64(1)), Err(Error::TypeMismatch));
+        assert_eq!(writer.position(), compact_len);
+        
+        let mut reader = VarintValueReader::new(&compact[..compact_len]).unwrap();
+        assert_eq!(reader.type_tag(), VarintTypeTag::U32);
+        for i in 0..100u32 {
+            assert_eq!(reader.read(), Ok(VarintValue::U32(i * 1000)));
+        }
+        assert!(reader.is_empty());
+        assert_eq!(reader.read(), Err(Error::InputTooShort));
+        
+        assert!(VarintValueWriter::new(VarintTypeTag::I8, &mut []).is_err());
+        assert_eq!(VarintValueReader::new(&[]).err(), Some(Error::InputTooShort));
+        assert_eq!(VarintValueReader::new(&[0x1F]).err(), Some(Error::InvalidEncoding { offset: 0 }));
+    }
+    
+    #[test]
+    fn test_try_from_slice() -> Result<(), Error> {
+        let mut buf = [0u8; 8];
+        let bytes_written = varint!(i16: -1000).to_bytes(&mut buf)?;
+        
+        let value: VarintValue = (&buf[..bytes_written]).try_into()?;
+        assert_eq!(value, varint!(i16: -1000));
+        
+        // Trailing bytes are ignored
+        let value: VarintValue = (&buf[..]).try_into()?;
+        assert_eq!(value, varint!(i16: -1000));
+        
+        assert_eq!(VarintValue::try_from(&[][..]), Err(Error::InputTooShort));
+        assert_eq!(VarintValue::try_from(&[0xFF][..]), Err(Error::InvalidEncoding { offset: 0 }));
+        Ok(())
     }
-} 
\ No newline at end of file
+    
+    #[test]
+    fn test_varint_values_from() {
+        use self::std::vec::Vec;
+        
+        // A zero ahead of other values still keeps its payload byte
+        let values = [varint!(u8: 0), varint!(u8: 200), varint!(i16: -300), varint!(u32: 70000)];
+        let mut buf = [0u8; 16];
+        let pos = to_bytes_batch(&values, &mut buf).unwrap();
+        
+        let decoded: Vec<VarintValue> = varint_values_from(&buf[..pos]).collect::<Result<_, _>>().unwrap();
+        assert_eq!(decoded, values);
+        
+        // The error is yielded once, then iteration stops
+        let mut iter = varint_values_from(&[0x00, 0x05, 0xFF, 0x00]);
+        assert_eq!(iter.next(), Some(Ok(varint!(u8: 5))));
+        assert_eq!(iter.next(), Some(Err(Error::InvalidEncoding { offset: 0 })));
+        assert_eq!(iter.next(), None);
+    }
+    
+    #[test]
+    fn test_narrow_and_widen() {
+        assert_eq!(VarintValue::U64(300).narrow(), Some(VarintValue::U16(300)));
+        assert_eq!(VarintValue::I32(-5).narrow(), Some(VarintValue::I8(-5)));
+        assert_eq!(VarintValue::U128(u64::MAX as u128).narrow(), Some(VarintValue::U64(u64::MAX)));
+        assert_eq!(VarintValue::I64(-129).narrow(), Some(VarintValue::I16(-129)));
+        
+        // Already as narrow as the value allows
+        assert_eq!(VarintValue::U8(5).narrow(), None);
+        assert_eq!(VarintValue::U32(70000).narrow(), None);
+        assert_eq!(VarintValue::U128(u128::MAX).narrow(), None);
+        assert_eq!(VarintValue::I128(i128::MIN).narrow(), None);
+        
+        assert_eq!(VarintValue::U8(5).widen_to(VarintTypeTag::U64), Some(VarintValue::U64(5)));
+        assert_eq!(VarintValue::I8(-5).widen_to(VarintTypeTag::I128), Some(VarintValue::I128(-5)));
+        assert_eq!(VarintValue::U16(7).widen_to(VarintTypeTag::U16), Some(VarintValue::U16(7)));
+        
+        // Narrower targets and signedness changes are refused
+        assert_eq!(VarintValue::U64(5).widen_to(VarintTypeTag::U8), None);
+        assert_eq!(VarintValue::U8(5).widen_to(VarintTypeTag::I64), None);
+        assert_eq!(VarintValue::I8(5).widen_to(VarintTypeTag::U64), None);
+    }
+    
+    #[test]
+    fn test_narrowest_from_integers() {
+        assert_eq!(VarintValue::narrowest_unsigned(0), VarintValue::U8(0));
+        assert_eq!(VarintValue::narrowest_unsigned(300), VarintValue::U16(300));
+        assert_eq!(VarintValue::narrowest_unsigned(u64::MAX as u128), VarintValue::U64(u64::MAX));
+        assert_eq!(VarintValue::narrowest_unsigned(u128::MAX), VarintValue::U128(u128::MAX));
+        
+        assert_eq!(VarintValue::narrowest_signed(-1), VarintValue::I8(-1));
+        assert_eq!(VarintValue::narrowest_signed(-129), VarintValue::I16(-129));
+        assert_eq!(VarintValue::narrowest_signed(i32::MAX as i128), VarintValue::I32(i32::MAX));
+        assert_eq!(VarintValue::narrowest_signed(i128::MIN), VarintValue::I128(i128::MIN));
+    }
+    
+    #[test]
+    fn test_type_id_table() {
+        let tags = [
+            VarintTypeTag::U8, VarintTypeTag::U16, VarintTypeTag::U32, VarintTypeTag::U64, VarintTypeTag::U128,
+            VarintTypeTag::I8, VarintTypeTag::I16, VarintTypeTag::I32, VarintTypeTag::I64, VarintTypeTag::I128,
+        ];
+        let ids = [0x00, 0x01, 0x02, 0x03, 0x04, 0x20, 0x21, 0x22, 0x23, 0x24];
+        
+        for (tag, id) in tags.into_iter().zip(ids) {
+            assert_eq!(type_id_for(tag), id);
+            assert_eq!(tag_for_type_id(id), Some(tag));
+        }
+        
+        // Usable in const contexts
+        const U32_ID: u8 = type_id_for(VarintTypeTag::U32);
+        assert_eq!(VarintValue::U32(1).get_type_id(), U32_ID);
+        
+        // Reserved signedness bits and size codes
+        for reserved in [0x05, 0x1F, 0x25, 0x40, 0x60, 0x80, 0xFF] {
+            assert_eq!(tag_for_type_id(reserved), None);
+        }
+    }
+    
+    #[test]
+    fn test_invalid_encoding_offset() {
+        // The type byte is always the first byte of a value
+        let err = VarintValue::from_bytes(&[0xFF, 0x00]).unwrap_err();
+        assert_eq!(err, Error::InvalidEncoding { offset: 0 });
+        assert_eq!(err.offset(), Some(0));
+        
+        assert_eq!(Error::Overflow { bytes_read: 2 }.offset(), None);
+    }
+    
+    #[test]
+    fn test_from_bytes_exact() {
+        let mut buffer = [0u8; 8];
+        let bytes_written = VarintValue::U32(300).to_bytes(&mut buffer).unwrap();
+        
+        assert_eq!(VarintValue::from_bytes_exact(&buffer[..bytes_written]), Ok(VarintValue::U32(300)));
+        assert_eq!(VarintValue::from_bytes_exact(&buffer[..bytes_written + 1]), Err(Error::TrailingData));
+        
+        // Compact zero is a single byte
+        assert_eq!(VarintValue::from_bytes_exact(&[0x22]), Ok(VarintValue::I32(0)));
+        assert_eq!(VarintValue::from_bytes_exact(&[]), Err(Error::InputTooShort));
+    }
+    
+    #[test]
+    fn test_checked_arithmetic() {
+        assert_eq!(VarintValue::U8(200).checked_add(&VarintValue::U8(100)), None);
+        assert_eq!(VarintValue::U16(200).checked_add(&VarintValue::U16(100)), Some(VarintValue::U16(300)));
+        
+        assert_eq!(VarintValue::I8(-100).checked_sub(&VarintValue::I8(28)), Some(VarintValue::I8(-128)));
+        assert_eq!(VarintValue::I8(-100).checked_sub(&VarintValue::I8(29)), None);
+        assert_eq!(VarintValue::U32(0).checked_sub(&VarintValue::U32(1)), None);
+        
+        assert_eq!(VarintValue::I64(-3).checked_mul(&VarintValue::I64(7)), Some(VarintValue::I64(-21)));
+        assert_eq!(VarintValue::U128(u128::MAX).checked_mul(&VarintValue::U128(2)), None);
+        
+        // No promotion between variants, even when the result would fit
+        assert_eq!(VarintValue::U8(1).checked_add(&VarintValue::U16(1)), None);
+        assert_eq!(VarintValue::U32(1).checked_add(&VarintValue::I32(1)), None);
+    }
+}
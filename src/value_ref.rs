@@ -0,0 +1,242 @@
+use crate::error::Error;
+use crate::encoding::varint_size;
+use crate::framing::{encode_bytes, decode_bytes};
+use crate::value::{VarintValue, TYPE_BITS_BYTES};
+
+/// Borrowing companion of `VarintValue` that can also hold a byte string
+///
+/// Integers use the same type bytes as `VarintValue`, but like `DynValue::Int`
+/// the payload is always written, so zero takes two bytes rather than the
+/// single type byte of `VarintValue::to_bytes`. That keeps a zero from reading
+/// the next value's type byte as its payload. `Bytes` is written as its own type
+/// byte followed by a varint length and the raw bytes, matching
+/// `DynValue::Bytes`. Decoding borrows the bytes from the input, so no
+/// allocation is needed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum VarintValueRef<'a> {
+    /// Unsigned 8-bit integer
+    U8(u8),
+    /// Unsigned 16-bit integer
+    U16(u16),
+    /// Unsigned 32-bit integer
+    U32(u32),
+    /// Unsigned 64-bit integer
+    U64(u64),
+    /// Unsigned 128-bit integer
+    U128(u128),
+    /// Signed 8-bit integer
+    I8(i8),
+    /// Signed 16-bit integer
+    I16(i16),
+    /// Signed 32-bit integer
+    I32(i32),
+    /// Signed 64-bit integer
+    I64(i64),
+    /// Signed 128-bit integer
+    I128(i128),
+    /// Raw byte string, borrowed from the input when decoding
+    Bytes(&'a [u8]),
+}
+
+impl<'a> VarintValueRef<'a> {
+    /// Returns the integer value, or `None` for `Bytes`
+    pub fn as_int(&self) -> Option<VarintValue> {
+        self.split().ok()
+    }
+    
+    /// Returns the byte string, or `None` for integers
+    pub fn as_bytes(&self) -> Option<&'a [u8]> {
+        self.split().err()
+    }
+    
+    /// Returns the number of bytes needed to serialize this value
+    pub fn serialized_size(&self) -> usize {
+        match self.split() {
+            Ok(value) => value.framed_size(),
+            Err(bytes) => 1 + varint_size(bytes.len()) + bytes.len(),
+        }
+    }
+    
+    /// Serializes the value into a byte buffer
+    ///
+    /// Integers are written with their payload even when zero, see the type docs.
+    ///
+    /// Returns the number of bytes written
+    ///
+    /// # Errors
+    /// Returns `Error::BufferTooSmall` if the buffer cannot hold the value
+    pub fn to_bytes(&self, buffer: &mut [u8]) -> Result<usize, Error> {
+        let needed = self.serialized_size();
+        if buffer.len() < needed {
+            return Err(Error::buffer_too_small(needed, buffer.len()));
+        }
+        
+        match self.split() {
+            Ok(value) => value.encode_framed(buffer),
+            Err(bytes) => {
+                buffer[0] = TYPE_BITS_BYTES;
+                Ok(1 + encode_bytes(bytes, &mut buffer[1..])?)
+            }
+        }
+    }
+    
+    /// Deserializes a value from a byte buffer, borrowing any byte string from it
+    ///
+    /// Returns the value and the number of bytes read
+    ///
+    /// # Errors
+    /// * Returns `Error::InputTooShort` if the input ends before the value does
+    /// * Returns `Error::InvalidEncoding` if the type byte is not recognised
+    /// * Returns any error from decoding an integer payload
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<(Self, usize), Error> {
+        let type_byte = *bytes.first().ok_or_else(Error::input_too_short)?;
+        
+        match type_byte {
+            TYPE_BITS_BYTES => {
+                let (data, bytes_read) = decode_bytes(&bytes[1..])?;
+                Ok((VarintValueRef::Bytes(data), 1 + bytes_read))
+            }
+            _ => {
+                let (value, bytes_read) = VarintValue::decode_payload(type_byte, &bytes[1..])?;
+                Ok((VarintValueRef::from(value), 1 + bytes_read))
+            }
+        }
+    }
+    
+    // Separates integers, which behave exactly like a VarintValue, from byte strings
+    fn split(&self) -> Result<VarintValue, &'a [u8]> {
+        match *self {
+            VarintValueRef::U8(val) => Ok(VarintValue::U8(val)),
+            VarintValueRef::U16(val) => Ok(VarintValue::U16(val)),
+            VarintValueRef::U32(val) => Ok(VarintValue::U32(val)),
+            VarintValueRef::U64(val) => Ok(VarintValue::U64(val)),
+            VarintValueRef::U128(val) => Ok(VarintValue::U128(val)),
+            VarintValueRef::I8(val) => Ok(VarintValue::I8(val)),
+            VarintValueRef::I16(val) => Ok(VarintValue::I16(val)),
+            VarintValueRef::I32(val) => Ok(VarintValue::I32(val)),
+            VarintValueRef::I64(val) => Ok(VarintValue::I64(val)),
+            VarintValueRef::I128(val) => Ok(VarintValue::I128(val)),
+            VarintValueRef::Bytes(bytes) => Err(bytes),
+        }
+    }
+}
+
+impl From<VarintValue> for VarintValueRef<'_> {
+    fn from(value: VarintValue) -> Self {
+        match value {
+            VarintValue::U8(val) => VarintValueRef::U8(val),
+            VarintValue::U16(val) => VarintValueRef::U16(val),
+            VarintValue::U32(val) => VarintValueRef::U32(val),
+            VarintValue::U64(val) => VarintValueRef::U64(val),
+            VarintValue::U128(val) => VarintValueRef::U128(val),
+            VarintValue::I8(val) => VarintValueRef::I8(val),
+            VarintValue::I16(val) => VarintValueRef::I16(val),
+            VarintValue::I32(val) => VarintValueRef::I32(val),
+            VarintValue::I64(val) => VarintValueRef::I64(val),
+            VarintValue::I128(val) => VarintValueRef::I128(val),
+        }
+    }
+}
+
+impl<'a> From<&'a [u8]> for VarintValueRef<'a> {
+    fn from(bytes: &'a [u8]) -> Self {
+        VarintValueRef::Bytes(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_mixed_stream_round_trip() {
+        let values = [
+            VarintValueRef::U32(1),
+            VarintValueRef::Bytes(&[1, 2, 3]),
+            VarintValueRef::I16(-5),
+        ];
+        
+        let mut buf = [0u8; 32];
+        let mut pos = 0;
+        for value in &values {
+            pos += value.to_bytes(&mut buf[pos..]).unwrap();
+        }
+        assert_eq!(pos, values.iter().map(VarintValueRef::serialized_size).sum::<usize>());
+        assert_eq!(&buf[..pos], &[0x02, 0x01, 0x40, 0x03, 1, 2, 3, 0x21, 0x09]);
+        
+        let mut decoded = [VarintValueRef::U8(0); 3];
+        let mut read = 0;
+        for slot in &mut decoded {
+            let (value, bytes_read) = VarintValueRef::from_bytes(&buf[read..pos]).unwrap();
+            *slot = value;
+            read += bytes_read;
+        }
+        assert_eq!(decoded, values);
+        assert_eq!(read, pos);
+        
+        // The byte string is borrowed from the input
+        let bytes = decoded[1].as_bytes().unwrap();
+        assert_eq!(bytes.as_ptr(), buf[4..].as_ptr());
+    }
+    
+    #[test]
+    fn test_integers_match_varint_value() {
+        let mut ref_buf = [0u8; 8];
+        let mut value_buf = [0u8; 8];
+        
+        for value in [VarintValue::U8(1), VarintValue::U64(300), VarintValue::I32(-1)] {
+            let bytes_written = VarintValueRef::from(value).to_bytes(&mut ref_buf).unwrap();
+            assert_eq!(value.to_bytes(&mut value_buf), Ok(bytes_written));
+            assert_eq!(ref_buf[..bytes_written], value_buf[..bytes_written]);
+            
+            let (decoded, _) = VarintValueRef::from_bytes(&ref_buf[..bytes_written]).unwrap();
+            assert_eq!(decoded.as_int(), Some(value));
+        }
+        
+        assert_eq!(VarintValueRef::from(&[7u8][..]).as_int(), None);
+        
+        // Zero keeps its payload byte instead of the compact form
+        let bytes_written = VarintValueRef::U32(0).to_bytes(&mut ref_buf).unwrap();
+        assert_eq!(&ref_buf[..bytes_written], &[0x02, 0x00]);
+        assert_eq!(VarintValueRef::U32(0).serialized_size(), 2);
+    }
+    
+    #[test]
+    fn test_zero_next_to_bytes_round_trip() {
+        let values = [
+            VarintValueRef::U32(0),
+            VarintValueRef::Bytes(&[1, 2, 3]),
+            VarintValueRef::I64(0),
+        ];
+        
+        let mut buf = [0u8; 16];
+        let mut pos = 0;
+        for value in &values {
+            pos += value.to_bytes(&mut buf[pos..]).unwrap();
+        }
+        assert_eq!(&buf[..pos], &[0x02, 0x00, 0x40, 0x03, 1, 2, 3, 0x23, 0x00]);
+        
+        let mut read = 0;
+        for value in &values {
+            let (decoded, bytes_read) = VarintValueRef::from_bytes(&buf[read..pos]).unwrap();
+            assert_eq!(decoded, *value);
+            read += bytes_read;
+        }
+        assert_eq!(read, pos);
+    }
+    
+    #[test]
+    fn test_errors() {
+        let mut small = [0u8; 4];
+        assert_eq!(
+            VarintValueRef::Bytes(&[1, 2, 3]).to_bytes(&mut small),
+            Err(Error::BufferTooSmall { needed: 5, actual: 4 })
+        );
+        
+        // Missing length, and a length longer than the input
+        assert_eq!(VarintValueRef::from_bytes(&[0x40]), Err(Error::InputTooShort));
+        assert_eq!(VarintValueRef::from_bytes(&[0x40, 0x03, 1]), Err(Error::InputTooShort));
+        assert_eq!(VarintValueRef::from_bytes(&[0x41, 0x00]), Err(Error::InvalidEncoding { offset: 0 }));
+        assert_eq!(VarintValueRef::from_bytes(&[]), Err(Error::InputTooShort));
+    }
+}
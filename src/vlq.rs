@@ -0,0 +1,126 @@
+use crate::error::Error;
+
+/// Largest value a 4-byte MIDI variable-length quantity can hold
+pub const VLQ_MAX: u32 = 0x0FFF_FFFF;
+
+/// Calculates the number of bytes needed to encode a value as a MIDI variable-length quantity
+pub fn vlq_size(value: u32) -> usize {
+    let bits_needed = 32 - value.leading_zeros() as usize;
+    bits_needed.div_ceil(7).max(1)
+}
+
+/// Encodes a u32 as a MIDI variable-length quantity
+///
+/// The most significant 7-bit group comes first, and every byte but the last
+/// has its high bit set.
+///
+/// Returns the number of bytes written
+///
+/// # Parameters
+/// * `value` - The value to encode, at most `VLQ_MAX`
+/// * `buf` - Output buffer
+///
+/// # Errors
+/// * Returns `Error::ValueTooLarge` if the value is larger than `VLQ_MAX`, with the
+///   unbounded encoding's size and the 4-byte limit
+/// * Returns `Error::BufferTooSmall` if the buffer is too small
+pub fn encode_vlq(value: u32, buf: &mut [u8]) -> Result<usize, Error> {
+    let needed_size = vlq_size(value);
+    
+    if value > VLQ_MAX {
+        return Err(Error::value_too_large(needed_size, 4));
+    }
+    
+    if buf.len() < needed_size {
+        return Err(Error::buffer_too_small(needed_size, buf.len()));
+    }
+    
+    let mut val = value;
+    buf[needed_size - 1] = (val & 0x7F) as u8;
+    for byte in buf[..needed_size - 1].iter_mut().rev() {
+        val >>= 7;
+        *byte = (val & 0x7F) as u8 | 0x80;
+    }
+    
+    Ok(needed_size)
+}
+
+/// Decodes a u32 from a MIDI variable-length quantity
+///
+/// Returns the decoded value and the number of bytes read
+///
+/// # Parameters
+/// * `buf` - Input buffer containing the variable-length quantity
+///
+/// # Errors
+/// * Returns `Error::InputTooShort` if the input buffer is empty
+/// * Returns `Error::UnexpectedEof` if the input ends after a byte with the continuation bit set
+/// * Returns `Error::Overflow` if the quantity is longer than 4 bytes
+pub fn decode_vlq(buf: &[u8]) -> Result<(u32, usize), Error> {
+    let mut result = 0u32;
+    
+    for i in 0..4 {
+        let byte = match buf.get(i) {
+            Some(&byte) => byte,
+            None if i == 0 => return Err(Error::input_too_short()),
+            None => return Err(Error::unexpected_eof()),
+        };
+        
+        result = (result << 7) | (byte & 0x7F) as u32;
+        
+        // Check if done
+        if byte & 0x80 == 0 {
+            return Ok((result, i + 1));
+        }
+    }
+    
+    Err(Error::overflow(4))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_midi_spec_examples() {
+        let cases: [(u32, &[u8]); 12] = [
+            (0x00000000, &[0x00]),
+            (0x00000040, &[0x40]),
+            (0x0000007F, &[0x7F]),
+            (0x00000080, &[0x81, 0x00]),
+            (0x00002000, &[0xC0, 0x00]),
+            (0x00003FFF, &[0xFF, 0x7F]),
+            (0x00004000, &[0x81, 0x80, 0x00]),
+            (0x00100000, &[0xC0, 0x80, 0x00]),
+            (0x001FFFFF, &[0xFF, 0xFF, 0x7F]),
+            (0x00200000, &[0x81, 0x80, 0x80, 0x00]),
+            (0x08000000, &[0xC0, 0x80, 0x80, 0x00]),
+            (0x0FFFFFFF, &[0xFF, 0xFF, 0xFF, 0x7F]),
+        ];
+        
+        for (value, expected) in cases {
+            let mut buf = [0u8; 4];
+            let bytes_written = encode_vlq(value, &mut buf).unwrap();
+            assert_eq!(&buf[..bytes_written], expected, "Encoding mismatch for {:#X}", value);
+            assert_eq!(vlq_size(value), expected.len());
+            assert_eq!(decode_vlq(expected), Ok((value, expected.len())));
+        }
+    }
+    
+    #[test]
+    fn test_errors() {
+        let mut buf = [0u8; 4];
+        assert_eq!(encode_vlq(VLQ_MAX + 1, &mut buf), Err(Error::ValueTooLarge { size: 5, max_len: 4 }));
+        assert_eq!(
+            encode_vlq(0x4000, &mut buf[..2]),
+            Err(Error::BufferTooSmall { needed: 3, actual: 2 })
+        );
+        
+        assert_eq!(decode_vlq(&[]), Err(Error::InputTooShort));
+        assert_eq!(decode_vlq(&[0x81, 0x80]), Err(Error::UnexpectedEof));
+        assert_eq!(decode_vlq(&[0x81, 0x80, 0x80, 0x80, 0x00]), Err(Error::Overflow { bytes_read: 4 }));
+        
+        // Trailing bytes are left alone
+        assert_eq!(decode_vlq(&[0x81, 0x00, 0xFF]), Ok((0x80, 2)));
+    }
+}
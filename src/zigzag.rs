@@ -1,3 +1,7 @@
+use core::num::{
+    NonZeroU8, NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU128, NonZeroUsize,
+    NonZeroI8, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI128, NonZeroIsize,
+};
 use crate::error::Error;
 use crate::traits::VarInt;
 use crate::encoding::{encode, decode};
@@ -39,6 +43,57 @@ impl_zigzag!(i16, u16, 16);
 impl_zigzag!(i32, u32, 32);
 impl_zigzag!(i64, u64, 64);
 impl_zigzag!(i128, u128, 128);
+impl_zigzag!(isize, usize, usize::BITS);
+
+// ZigZag maps non-zero values to non-zero values, so the non-zero types stay non-zero
+macro_rules! impl_nonzero_zigzag {
+    ($signed:ty, $inner:ty, $unsigned:ty) => {
+        impl ZigZag for $signed {
+            type Unsigned = $unsigned;
+            
+            #[inline]
+            fn zigzag_encode(self) -> Self::Unsigned {
+                <$unsigned>::new(self.get().zigzag_encode()).unwrap()
+            }
+            
+            #[inline]
+            fn zigzag_decode(value: Self::Unsigned) -> Self {
+                Self::new(<$inner>::zigzag_decode(value.get())).unwrap()
+            }
+        }
+    };
+}
+
+impl_nonzero_zigzag!(NonZeroI8, i8, NonZeroU8);
+impl_nonzero_zigzag!(NonZeroI16, i16, NonZeroU16);
+impl_nonzero_zigzag!(NonZeroI32, i32, NonZeroU32);
+impl_nonzero_zigzag!(NonZeroI64, i64, NonZeroU64);
+impl_nonzero_zigzag!(NonZeroI128, i128, NonZeroU128);
+impl_nonzero_zigzag!(NonZeroIsize, isize, NonZeroUsize);
+
+/// Apply the ZigZag transform without any varint framing
+///
+/// Maps 0, -1, 1, -2, ... to 0, 1, 2, 3, ...
+#[inline]
+pub fn zigzag_encode_value<S: ZigZag>(value: S) -> S::Unsigned {
+    value.zigzag_encode()
+}
+
+/// Invert the ZigZag transform without any varint framing
+#[inline]
+pub fn zigzag_decode_value<S: ZigZag>(value: S::Unsigned) -> S {
+    S::zigzag_decode(value)
+}
+
+/// Returns the number of bytes `encode_zigzag` would write for a value
+///
+/// Use this rather than `varint_size` to pre-size buffers for signed values:
+/// `varint_size(-1i32)` sizes the two's complement bit pattern (5 bytes), while
+/// the zigzag encoding of `-1i32` takes a single byte.
+#[inline]
+pub fn zigzag_size<S: ZigZag>(value: S) -> usize {
+    value.zigzag_encode().varint_size()
+}
 
 /// Encode a signed integer using ZigZag, then encode it as a varint
 ///
@@ -63,10 +118,21 @@ pub fn encode_zigzag<T: ZigZag>(value: T, buf: &mut [u8]) -> Result<usize, Error
 /// * `buf` - The input buffer containing the zigzag varint encoded value
 ///
 /// # Errors
-/// * Returns `Error::InputTooShort` if the input buffer is insufficient
+/// * Returns `Error::InputTooShort` if the input buffer is empty
+/// * Returns `Error::UnexpectedEof` if the input ends after a byte with the continuation bit set
 /// * Returns `Error::InvalidEncoding` if the varint encoding is invalid
 /// * Returns `Error::Overflow` if an overflow occurs during decoding
 pub fn decode_zigzag<T: ZigZag>(buf: &[u8]) -> Result<(T, usize), Error> {
     let (unsigned, bytes_read) = decode::<T::Unsigned>(buf)?;
     Ok((T::zigzag_decode(unsigned), bytes_read))
-} 
\ No newline at end of file
+} 
+
+/// Decode a zigzag-encoded signed integer, returning the rest of the buffer after it
+///
+/// # Errors
+/// Same as `decode_zigzag`
+#[inline]
+pub fn decode_zigzag_split<T: ZigZag>(buf: &[u8]) -> Result<(T, &[u8]), Error> {
+    let (value, bytes_read) = decode_zigzag(buf)?;
+    Ok((value, &buf[bytes_read..]))
+}